@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS opname_items;
+DROP TABLE IF EXISTS opnames;
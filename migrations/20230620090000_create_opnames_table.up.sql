@@ -0,0 +1,33 @@
+-- Add up migration script here
+CREATE TABLE opnames (
+    id uuid DEFAULT uuid_generate_v4(),
+    branch_id uuid NOT NULL,
+    created_by uuid NOT NULL,
+    status VARCHAR(50) NOT NULL DEFAULT 'OPEN', -- status of the count session | e.g. "OPEN" or "CLOSED"
+    note VARCHAR(255),
+    closed_by uuid,
+    closed_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP,
+    PRIMARY KEY (id),
+    FOREIGN KEY (branch_id) REFERENCES branches(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (closed_by) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE opname_items (
+    id uuid DEFAULT uuid_generate_v4(),
+    opname_id uuid NOT NULL,
+    specification_id uuid NOT NULL,
+    system_quantity INTEGER NOT NULL,
+    counted_quantity INTEGER,
+    unit_price FLOAT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP,
+    PRIMARY KEY (id),
+    UNIQUE (opname_id, specification_id),
+    FOREIGN KEY (opname_id) REFERENCES opnames(id) ON DELETE CASCADE,
+    FOREIGN KEY (specification_id) REFERENCES specifications(id) ON DELETE CASCADE
+);
@@ -0,0 +1,21 @@
+-- Add up migration script here
+CREATE TABLE stock_balances (
+    id uuid DEFAULT uuid_generate_v4(),
+    specification_id uuid NOT NULL,
+    quantity BIGINT NOT NULL DEFAULT 0,
+    value FLOAT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (id),
+    UNIQUE (specification_id),
+    FOREIGN KEY (specification_id) REFERENCES specifications(id) ON DELETE CASCADE
+);
+
+INSERT INTO stock_balances (specification_id, quantity, value)
+SELECT
+    sh.specification_id,
+    sum(CASE WHEN sh.flow_type = 'IN' THEN sh.quantity ELSE -sh.quantity END),
+    sum(CASE WHEN sh.flow_type = 'IN' THEN coalesce(sh.price, 0) ELSE -coalesce(sh.price, 0) END)
+FROM specification_histories sh
+WHERE sh.deleted_at IS NULL
+GROUP BY sh.specification_id;
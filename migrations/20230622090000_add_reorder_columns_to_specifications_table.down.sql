@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE specifications DROP COLUMN reorder_quantity;
+ALTER TABLE specifications DROP COLUMN min_stock;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE specifications ADD COLUMN min_stock INTEGER;
+ALTER TABLE specifications ADD COLUMN reorder_quantity INTEGER;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE specification_histories DROP COLUMN supplier_id;
+DROP TABLE IF EXISTS specification_suppliers;
+DROP TABLE IF EXISTS suppliers;
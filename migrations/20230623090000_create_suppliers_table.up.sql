@@ -0,0 +1,30 @@
+-- Add up migration script here
+CREATE TABLE suppliers (
+    id uuid DEFAULT uuid_generate_v4(),
+    branch_id uuid NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    contact VARCHAR(255),
+    lead_time_days INTEGER,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP,
+    PRIMARY KEY (id),
+    FOREIGN KEY (branch_id) REFERENCES branches(id) ON DELETE CASCADE
+);
+
+CREATE TABLE specification_suppliers (
+    id uuid DEFAULT uuid_generate_v4(),
+    specification_id uuid NOT NULL,
+    supplier_id uuid NOT NULL,
+    pack_size INTEGER NOT NULL,
+    price FLOAT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP,
+    PRIMARY KEY (id),
+    UNIQUE (specification_id, supplier_id),
+    FOREIGN KEY (specification_id) REFERENCES specifications(id) ON DELETE CASCADE,
+    FOREIGN KEY (supplier_id) REFERENCES suppliers(id) ON DELETE CASCADE
+);
+
+ALTER TABLE specification_histories ADD COLUMN supplier_id uuid REFERENCES suppliers(id) ON DELETE SET NULL;
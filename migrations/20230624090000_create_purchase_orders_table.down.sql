@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS purchase_order_receipt_items;
+DROP TABLE IF EXISTS purchase_order_receipts;
+DROP TABLE IF EXISTS purchase_order_items;
+DROP TABLE IF EXISTS purchase_orders;
@@ -0,0 +1,65 @@
+-- Add up migration script here
+CREATE TABLE purchase_orders (
+    id uuid DEFAULT uuid_generate_v4(),
+    branch_id uuid NOT NULL,
+    supplier_id uuid,
+    created_by uuid NOT NULL,
+    status VARCHAR(50) NOT NULL DEFAULT 'DRAFT', -- status of the purchase order | e.g. "DRAFT", "APPROVED", "PARTIALLY_RECEIVED" or "CLOSED"
+    note VARCHAR(255),
+    approved_by uuid,
+    approved_at TIMESTAMP,
+    closed_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP,
+    PRIMARY KEY (id),
+    FOREIGN KEY (branch_id) REFERENCES branches(id) ON DELETE CASCADE,
+    FOREIGN KEY (supplier_id) REFERENCES suppliers(id) ON DELETE SET NULL,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (approved_by) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE purchase_order_items (
+    id uuid DEFAULT uuid_generate_v4(),
+    purchase_order_id uuid NOT NULL,
+    specification_id uuid NOT NULL,
+    quantity INTEGER NOT NULL,
+    received_quantity INTEGER NOT NULL DEFAULT 0,
+    unit_price FLOAT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP,
+    PRIMARY KEY (id),
+    UNIQUE (purchase_order_id, specification_id),
+    CHECK (received_quantity <= quantity),
+    FOREIGN KEY (purchase_order_id) REFERENCES purchase_orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (specification_id) REFERENCES specifications(id) ON DELETE CASCADE
+);
+
+CREATE TABLE purchase_order_receipts (
+    id uuid DEFAULT uuid_generate_v4(),
+    purchase_order_id uuid NOT NULL,
+    received_by uuid NOT NULL,
+    note VARCHAR(255),
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP,
+    PRIMARY KEY (id),
+    FOREIGN KEY (purchase_order_id) REFERENCES purchase_orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (received_by) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE purchase_order_receipt_items (
+    id uuid DEFAULT uuid_generate_v4(),
+    purchase_order_receipt_id uuid NOT NULL,
+    purchase_order_item_id uuid NOT NULL,
+    specification_history_id uuid NOT NULL,
+    quantity INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP,
+    PRIMARY KEY (id),
+    FOREIGN KEY (purchase_order_receipt_id) REFERENCES purchase_order_receipts(id) ON DELETE CASCADE,
+    FOREIGN KEY (purchase_order_item_id) REFERENCES purchase_order_items(id) ON DELETE CASCADE,
+    FOREIGN KEY (specification_history_id) REFERENCES specification_histories(id) ON DELETE CASCADE
+);
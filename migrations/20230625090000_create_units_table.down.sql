@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE specifications DROP COLUMN unit_id;
+DROP TABLE IF EXISTS units;
@@ -0,0 +1,31 @@
+-- Add up migration script here
+CREATE TABLE units (
+    id uuid DEFAULT uuid_generate_v4(),
+    branch_id uuid, -- NULL for the global catalog, set for branch specific units such as pack or carton
+    code VARCHAR(50) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    dimension VARCHAR(50) NOT NULL, -- dimension of the unit | e.g. "MASS", "VOLUME" or "COUNT"
+    factor FLOAT NOT NULL, -- how many base units of the dimension (g, ml, pcs) one of this unit holds
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP,
+    PRIMARY KEY (id),
+    CHECK (factor > 0),
+    FOREIGN KEY (branch_id) REFERENCES branches(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX units_branch_id_code_unique ON units (coalesce(branch_id, '00000000-0000-0000-0000-000000000000'), code);
+
+INSERT INTO units (code, name, dimension, factor) VALUES
+    ('g', 'gram', 'MASS', 1),
+    ('kg', 'kilogram', 'MASS', 1000),
+    ('ml', 'milliliter', 'VOLUME', 1),
+    ('l', 'liter', 'VOLUME', 1000),
+    ('pcs', 'piece', 'COUNT', 1);
+
+ALTER TABLE specifications ADD COLUMN unit_id uuid REFERENCES units(id) ON DELETE SET NULL;
+
+UPDATE specifications s
+SET unit_id = u.id
+FROM units u
+WHERE u.branch_id IS NULL AND lower(s.unit_name) IN (u.code, u.name);
@@ -0,0 +1,12 @@
+-- Add down migration script here
+ALTER TABLE purchase_order_receipt_items ALTER COLUMN quantity TYPE INTEGER USING round(quantity);
+ALTER TABLE purchase_order_items ALTER COLUMN received_quantity TYPE INTEGER USING round(received_quantity);
+ALTER TABLE purchase_order_items ALTER COLUMN quantity TYPE INTEGER USING round(quantity);
+ALTER TABLE opname_items ALTER COLUMN counted_quantity TYPE INTEGER USING round(counted_quantity);
+ALTER TABLE opname_items ALTER COLUMN system_quantity TYPE INTEGER USING round(system_quantity);
+ALTER TABLE specifications ALTER COLUMN reorder_quantity TYPE INTEGER USING round(reorder_quantity);
+ALTER TABLE specifications ALTER COLUMN min_stock TYPE INTEGER USING round(min_stock);
+ALTER TABLE stock_balances ALTER COLUMN quantity TYPE BIGINT USING round(quantity);
+ALTER TABLE specification_histories ALTER COLUMN quantity TYPE INTEGER USING round(quantity);
+ALTER TABLE transaction_items ALTER COLUMN product_quantity TYPE INTEGER USING round(product_quantity);
+ALTER TABLE product_specifications ALTER COLUMN quantity TYPE INTEGER USING round(quantity);
@@ -0,0 +1,12 @@
+-- Add up migration script here
+ALTER TABLE product_specifications ALTER COLUMN quantity TYPE NUMERIC;
+ALTER TABLE transaction_items ALTER COLUMN product_quantity TYPE NUMERIC;
+ALTER TABLE specification_histories ALTER COLUMN quantity TYPE NUMERIC;
+ALTER TABLE stock_balances ALTER COLUMN quantity TYPE NUMERIC;
+ALTER TABLE specifications ALTER COLUMN min_stock TYPE NUMERIC;
+ALTER TABLE specifications ALTER COLUMN reorder_quantity TYPE NUMERIC;
+ALTER TABLE opname_items ALTER COLUMN system_quantity TYPE NUMERIC;
+ALTER TABLE opname_items ALTER COLUMN counted_quantity TYPE NUMERIC;
+ALTER TABLE purchase_order_items ALTER COLUMN quantity TYPE NUMERIC;
+ALTER TABLE purchase_order_items ALTER COLUMN received_quantity TYPE NUMERIC;
+ALTER TABLE purchase_order_receipt_items ALTER COLUMN quantity TYPE NUMERIC;
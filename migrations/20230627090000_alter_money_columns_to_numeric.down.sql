@@ -0,0 +1,12 @@
+-- Add down migration script here
+ALTER TABLE branches DROP COLUMN rounding_mode;
+ALTER TABLE branches DROP COLUMN price_scale;
+
+ALTER TABLE purchase_order_items ALTER COLUMN unit_price TYPE FLOAT;
+ALTER TABLE specification_suppliers ALTER COLUMN price TYPE FLOAT;
+ALTER TABLE opname_items ALTER COLUMN unit_price TYPE FLOAT;
+ALTER TABLE stock_balances ALTER COLUMN value TYPE FLOAT;
+ALTER TABLE specification_histories ALTER COLUMN unit_price TYPE FLOAT;
+ALTER TABLE specification_histories ALTER COLUMN price TYPE FLOAT;
+ALTER TABLE specifications ALTER COLUMN raw_price TYPE INTEGER USING round(raw_price);
+ALTER TABLE specifications ALTER COLUMN lowest_price TYPE FLOAT;
@@ -0,0 +1,12 @@
+-- Add up migration script here
+ALTER TABLE specifications ALTER COLUMN lowest_price TYPE NUMERIC;
+ALTER TABLE specifications ALTER COLUMN raw_price TYPE NUMERIC;
+ALTER TABLE specification_histories ALTER COLUMN price TYPE NUMERIC;
+ALTER TABLE specification_histories ALTER COLUMN unit_price TYPE NUMERIC;
+ALTER TABLE stock_balances ALTER COLUMN value TYPE NUMERIC;
+ALTER TABLE opname_items ALTER COLUMN unit_price TYPE NUMERIC;
+ALTER TABLE specification_suppliers ALTER COLUMN price TYPE NUMERIC;
+ALTER TABLE purchase_order_items ALTER COLUMN unit_price TYPE NUMERIC;
+
+ALTER TABLE branches ADD COLUMN price_scale INTEGER NOT NULL DEFAULT 2 CHECK (price_scale BETWEEN 0 AND 6);
+ALTER TABLE branches ADD COLUMN rounding_mode VARCHAR(50) NOT NULL DEFAULT 'HALF_UP'; -- rounding_mode of money amounts | e.g. "HALF_UP", "HALF_EVEN", "UP" or "DOWN"
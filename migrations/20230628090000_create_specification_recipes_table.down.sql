@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP FUNCTION IF EXISTS specification_unit_cost(uuid);
+DROP TABLE IF EXISTS specification_recipes;
+ALTER TABLE specifications DROP COLUMN recipe_yield;
@@ -0,0 +1,38 @@
+-- Add up migration script here
+ALTER TABLE specifications ADD COLUMN recipe_yield NUMERIC CHECK (recipe_yield > 0); -- quantity of the specification one batch of its recipe produces
+
+CREATE TABLE specification_recipes (
+    id uuid DEFAULT uuid_generate_v4(),
+    specification_id uuid NOT NULL,
+    component_specification_id uuid NOT NULL,
+    quantity NUMERIC NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP,
+    PRIMARY KEY (id),
+    UNIQUE (specification_id, component_specification_id),
+    CHECK (specification_id <> component_specification_id),
+    FOREIGN KEY (specification_id) REFERENCES specifications(id) ON DELETE CASCADE,
+    FOREIGN KEY (component_specification_id) REFERENCES specifications(id) ON DELETE CASCADE
+);
+
+-- unit cost of a specification rolled up through every level of its recipe tree
+CREATE FUNCTION specification_unit_cost(root_id uuid) RETURNS NUMERIC AS $$
+    WITH RECURSIVE tree AS (
+        SELECT s.id AS specification_id, 1::NUMERIC AS multiplier, ARRAY[s.id] AS path
+        FROM specifications s
+        WHERE s.id = root_id
+        UNION ALL
+        SELECT sr.component_specification_id, t.multiplier * sr.quantity / coalesce(s.recipe_yield, 1), t.path || sr.component_specification_id
+        FROM
+            tree t
+            INNER JOIN specifications s ON s.id = t.specification_id
+            INNER JOIN specification_recipes sr ON sr.specification_id = t.specification_id
+        WHERE NOT sr.component_specification_id = ANY(t.path)
+    )
+    SELECT sum(t.multiplier * s.lowest_price)
+    FROM
+        tree t
+        INNER JOIN specifications s ON s.id = t.specification_id
+    WHERE NOT EXISTS (SELECT 1 FROM specification_recipes sr WHERE sr.specification_id = t.specification_id)
+$$ LANGUAGE SQL STABLE;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS production_items;
+DROP TABLE IF EXISTS productions;
+ALTER TABLE specifications DROP COLUMN is_prepared;
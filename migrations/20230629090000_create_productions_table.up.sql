@@ -0,0 +1,38 @@
+-- Add up migration script here
+ALTER TABLE specifications ADD COLUMN is_prepared BOOLEAN NOT NULL DEFAULT false; -- made in production batches, sales deduct its own stock instead of its recipe components
+
+CREATE TABLE productions (
+    id uuid DEFAULT uuid_generate_v4(),
+    branch_id uuid NOT NULL,
+    specification_id uuid NOT NULL,
+    specification_history_id uuid,
+    created_by uuid NOT NULL,
+    quantity NUMERIC NOT NULL,
+    total_cost NUMERIC NOT NULL DEFAULT 0,
+    unit_cost NUMERIC NOT NULL DEFAULT 0,
+    note VARCHAR(255),
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP,
+    PRIMARY KEY (id),
+    FOREIGN KEY (branch_id) REFERENCES branches(id) ON DELETE CASCADE,
+    FOREIGN KEY (specification_id) REFERENCES specifications(id) ON DELETE CASCADE,
+    FOREIGN KEY (specification_history_id) REFERENCES specification_histories(id) ON DELETE SET NULL,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE production_items (
+    id uuid DEFAULT uuid_generate_v4(),
+    production_id uuid NOT NULL,
+    specification_id uuid NOT NULL,
+    specification_history_id uuid NOT NULL,
+    quantity NUMERIC NOT NULL,
+    price NUMERIC NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP,
+    PRIMARY KEY (id),
+    FOREIGN KEY (production_id) REFERENCES productions(id) ON DELETE CASCADE,
+    FOREIGN KEY (specification_id) REFERENCES specifications(id) ON DELETE CASCADE,
+    FOREIGN KEY (specification_history_id) REFERENCES specification_histories(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS specification_histories_specification_id_flow_type_index;
+ALTER TABLE branches DROP COLUMN costing_method;
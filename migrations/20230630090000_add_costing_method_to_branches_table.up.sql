@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE branches ADD COLUMN costing_method VARCHAR(50) NOT NULL DEFAULT 'WEIGHTED_AVERAGE'; -- costing_method of OUT movements | e.g. "WEIGHTED_AVERAGE" or "FIFO"
+
+CREATE INDEX specification_histories_specification_id_flow_type_index ON specification_histories (specification_id, flow_type, created_at);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE transactions DROP COLUMN void_reason;
+ALTER TABLE transactions DROP COLUMN voided_by;
+
+ALTER TABLE transaction_items DROP COLUMN refunded_quantity;
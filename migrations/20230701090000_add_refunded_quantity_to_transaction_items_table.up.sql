@@ -0,0 +1,5 @@
+-- Add up migration script here
+ALTER TABLE transaction_items ADD COLUMN refunded_quantity NUMERIC NOT NULL DEFAULT 0 CHECK (refunded_quantity >= 0 AND refunded_quantity <= product_quantity);
+
+ALTER TABLE transactions ADD COLUMN voided_by uuid REFERENCES users(id);
+ALTER TABLE transactions ADD COLUMN void_reason VARCHAR(255);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE transactions DROP CONSTRAINT transactions_branch_id_reference_id_unique;
+ALTER TABLE transactions DROP COLUMN reference_id;
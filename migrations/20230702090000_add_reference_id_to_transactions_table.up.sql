@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE transactions ADD COLUMN reference_id uuid; -- transaction_id sent by the POS, retries carry the same value
+ALTER TABLE transactions ADD CONSTRAINT transactions_branch_id_reference_id_unique UNIQUE (branch_id, reference_id);
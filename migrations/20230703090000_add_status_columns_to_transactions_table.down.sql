@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS stock_reservations;
+
+ALTER TABLE branches DROP COLUMN stock_deduction;
+
+ALTER TABLE transactions DROP COLUMN payment_status;
+ALTER TABLE transactions DROP COLUMN order_status;
@@ -0,0 +1,21 @@
+-- Add up migration script here
+ALTER TABLE transactions ADD COLUMN order_status VARCHAR(50) NOT NULL DEFAULT 'COMPLETED'; -- order_status of transaction | e.g. "PENDING", "COMPLETED" or "CANCELLED"
+ALTER TABLE transactions ADD COLUMN payment_status VARCHAR(50) NOT NULL DEFAULT 'PAID'; -- payment_status of transaction | e.g. "UNPAID" or "PAID"
+
+ALTER TABLE branches ADD COLUMN stock_deduction VARCHAR(50) NOT NULL DEFAULT 'ON_CREATE'; -- when pending orders take stock | e.g. "ON_CREATE" or "ON_COMPLETE"
+
+CREATE TABLE stock_reservations (
+    id uuid DEFAULT uuid_generate_v4(),
+    transaction_item_id uuid NOT NULL,
+    specification_id uuid NOT NULL,
+    quantity NUMERIC NOT NULL CHECK (quantity > 0),
+    status VARCHAR(50) NOT NULL DEFAULT 'RESERVED', -- status of reservation | e.g. "RESERVED", "DEDUCTED" or "RELEASED"
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP,
+    PRIMARY KEY (id),
+    FOREIGN KEY (transaction_item_id) REFERENCES transaction_items(id) ON DELETE CASCADE,
+    FOREIGN KEY (specification_id) REFERENCES specifications(id) ON DELETE CASCADE
+);
+
+CREATE INDEX stock_reservations_specification_id_status_index ON stock_reservations (specification_id, status);
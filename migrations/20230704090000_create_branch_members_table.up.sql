@@ -0,0 +1,20 @@
+-- Add up migration script here
+CREATE TABLE branch_members (
+    id uuid DEFAULT uuid_generate_v4(),
+    branch_id uuid NOT NULL,
+    user_id uuid NOT NULL,
+    role VARCHAR(50) NOT NULL, -- role of member | e.g. "OWNER", "MANAGER", "STAFF" or "AUDITOR"
+    invited_by uuid,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP,
+    PRIMARY KEY (id),
+    UNIQUE (branch_id, user_id),
+    FOREIGN KEY (branch_id) REFERENCES branches(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (invited_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- the single owner every branch had so far
+INSERT INTO branch_members (branch_id, user_id, role)
+SELECT id, user_id, 'OWNER' FROM branches;
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS oauth_access_tokens_user_id_index;
+ALTER TABLE oauth_access_tokens DROP COLUMN user_agent;
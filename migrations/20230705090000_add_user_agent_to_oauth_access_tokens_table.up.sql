@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE oauth_access_tokens ADD COLUMN user_agent VARCHAR(255);
+
+CREATE INDEX oauth_access_tokens_user_id_index ON oauth_access_tokens (user_id, created_at);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS oauth_refresh_tokens;
+
+ALTER TABLE oauth_access_tokens DROP COLUMN session_expires_at;
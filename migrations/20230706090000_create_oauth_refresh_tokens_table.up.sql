@@ -0,0 +1,18 @@
+-- Add up migration script here
+ALTER TABLE oauth_access_tokens ADD COLUMN session_expires_at TIMESTAMP;
+UPDATE oauth_access_tokens SET session_expires_at = expires_at;
+ALTER TABLE oauth_access_tokens ALTER COLUMN session_expires_at SET NOT NULL;
+
+CREATE TABLE oauth_refresh_tokens (
+    id uuid DEFAULT uuid_generate_v4(),
+    oauth_access_token_id uuid NOT NULL,
+    refresh_token VARCHAR(64) NOT NULL, -- sha256 of the token handed to the client
+    expires_at TIMESTAMP NOT NULL,
+    used_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP,
+    PRIMARY KEY (id),
+    UNIQUE (refresh_token),
+    FOREIGN KEY (oauth_access_token_id) REFERENCES oauth_access_tokens(id) ON DELETE CASCADE
+);
@@ -0,0 +1,19 @@
+-- Add up migration script here
+CREATE TABLE api_keys (
+    id uuid DEFAULT uuid_generate_v4(),
+    branch_id uuid NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    prefix VARCHAR(16) NOT NULL, -- first characters of the key to tell keys apart | e.g. "sk_3f9a1c2b"
+    key_hash VARCHAR(64) NOT NULL UNIQUE, -- sha256 of the key, the key itself is only shown once
+    scopes TEXT[] NOT NULL, -- granted scopes | e.g. "transactions:write" or "stock:read"
+    created_by uuid NOT NULL,
+    last_used_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP,
+    PRIMARY KEY (id),
+    FOREIGN KEY (branch_id) REFERENCES branches(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX api_keys_branch_id_index ON api_keys (branch_id);
@@ -0,0 +1,17 @@
+-- Add up migration script here
+ALTER TABLE users ADD COLUMN email_verified_at TIMESTAMP;
+
+CREATE TABLE user_tokens (
+    id uuid DEFAULT uuid_generate_v4(),
+    user_id uuid NOT NULL,
+    type VARCHAR(50) NOT NULL, -- purpose of the token | e.g. "PASSWORD_RESET" or "EMAIL_VERIFICATION"
+    token VARCHAR(64) NOT NULL UNIQUE, -- sha256 of the token sent by mail
+    expires_at TIMESTAMP NOT NULL,
+    used_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (id),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX user_tokens_user_id_type_index ON user_tokens (user_id, type);
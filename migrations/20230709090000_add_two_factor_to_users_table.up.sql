@@ -0,0 +1,19 @@
+-- Add up migration script here
+ALTER TABLE users ADD COLUMN totp_secret VARCHAR(64); -- base32 secret, set on setup and kept once enabled
+ALTER TABLE users ADD COLUMN totp_enabled_at TIMESTAMP;
+ALTER TABLE users ADD COLUMN totp_last_step BIGINT; -- time step of the last accepted code, so a code can't be replayed
+
+CREATE TABLE user_recovery_codes (
+    id uuid DEFAULT uuid_generate_v4(),
+    user_id uuid NOT NULL,
+    code VARCHAR(64) NOT NULL, -- sha256 of the recovery code
+    used_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (id),
+    UNIQUE (user_id, code),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- roles that must have two-factor enabled to access the branch | e.g. {"OWNER","MANAGER"}
+ALTER TABLE branches ADD COLUMN two_factor_roles TEXT[] NOT NULL DEFAULT '{}';
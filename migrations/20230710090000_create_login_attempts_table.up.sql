@@ -0,0 +1,15 @@
+-- Add up migration script here
+CREATE TABLE login_attempts (
+    id uuid DEFAULT uuid_generate_v4(),
+    email VARCHAR(255) NOT NULL,
+    user_id uuid, -- set when the email belongs to a user
+    ip_address VARCHAR(45) NOT NULL,
+    user_agent VARCHAR(255),
+    status VARCHAR(50) NOT NULL, -- outcome of the attempt | e.g. "SUCCESS", "FAILED", "LOCKED", "TWO_FACTOR_REQUIRED" or "TWO_FACTOR_FAILED"
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (id),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX login_attempts_email_created_at_index ON login_attempts (email, created_at);
+CREATE INDEX login_attempts_ip_address_created_at_index ON login_attempts (ip_address, created_at);
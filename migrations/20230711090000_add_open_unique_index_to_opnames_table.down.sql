@@ -0,0 +1,2 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS opnames_branch_id_open_unique;
@@ -0,0 +1,15 @@
+-- Add up migration script here
+-- only the latest open opname of a branch survives, older duplicates from concurrent opens are closed without adjustments
+UPDATE opnames o
+SET status = 'CLOSED', closed_at = now(), updated_at = now()
+WHERE o.status = 'OPEN'
+    AND o.deleted_at IS NULL
+    AND EXISTS (
+        SELECT 1 FROM opnames newer
+        WHERE newer.branch_id = o.branch_id
+            AND newer.status = 'OPEN'
+            AND newer.deleted_at IS NULL
+            AND (newer.created_at, newer.id) > (o.created_at, o.id)
+    );
+
+CREATE UNIQUE INDEX opnames_branch_id_open_unique ON opnames (branch_id) WHERE status = 'OPEN' AND deleted_at IS NULL;
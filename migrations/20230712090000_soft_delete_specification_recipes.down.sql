@@ -0,0 +1,26 @@
+-- Add down migration script here
+CREATE OR REPLACE FUNCTION specification_unit_cost(root_id uuid) RETURNS NUMERIC AS $$
+    WITH RECURSIVE tree AS (
+        SELECT s.id AS specification_id, 1::NUMERIC AS multiplier, ARRAY[s.id] AS path
+        FROM specifications s
+        WHERE s.id = root_id
+        UNION ALL
+        SELECT sr.component_specification_id, t.multiplier * sr.quantity / coalesce(s.recipe_yield, 1), t.path || sr.component_specification_id
+        FROM
+            tree t
+            INNER JOIN specifications s ON s.id = t.specification_id
+            INNER JOIN specification_recipes sr ON sr.specification_id = t.specification_id
+        WHERE NOT sr.component_specification_id = ANY(t.path)
+    )
+    SELECT sum(t.multiplier * s.lowest_price)
+    FROM
+        tree t
+        INNER JOIN specifications s ON s.id = t.specification_id
+    WHERE NOT EXISTS (SELECT 1 FROM specification_recipes sr WHERE sr.specification_id = t.specification_id)
+$$ LANGUAGE SQL STABLE;
+
+DROP INDEX IF EXISTS specification_recipes_specification_id_component_unique;
+
+DELETE FROM specification_recipes WHERE deleted_at IS NOT NULL;
+
+ALTER TABLE specification_recipes ADD CONSTRAINT specification_recipes_specification_id_component_specificat_key UNIQUE (specification_id, component_specification_id);
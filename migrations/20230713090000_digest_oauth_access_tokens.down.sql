@@ -0,0 +1,5 @@
+-- Add down migration script here
+-- the digests cannot be turned back into tokens, every session has to log in again
+UPDATE oauth_access_tokens SET revoked_at = now(), updated_at = now() WHERE revoked_at IS NULL;
+
+COMMENT ON COLUMN oauth_access_tokens.access_token IS NULL;
@@ -0,0 +1,5 @@
+-- Add up migration script here
+-- access tokens are looked up by their sha256 from now on, keep the live sessions working
+UPDATE oauth_access_tokens SET access_token = encode(sha256(access_token::bytea), 'hex');
+
+COMMENT ON COLUMN oauth_access_tokens.access_token IS 'sha256 of the token handed to the client';
@@ -17,12 +17,33 @@ pub struct DatabaseConfig {
     pub poolmaxsize: u32,
 }
 
+// SESSION_MAXCOUNT, SESSION_TTLDAYS and SESSION_ACCESSTTLMINUTES
+#[derive(Deserialize)]
+pub struct SessionConfig {
+    pub maxcount: Option<i64>,
+    pub ttldays: Option<i64>,
+    pub accessttlminutes: Option<i64>,
+}
+
+// MAIL_DRIVER, MAIL_HOST, MAIL_PORT, MAIL_USERNAME, MAIL_PASSWORD and MAIL_FROM
+#[derive(Deserialize)]
+pub struct MailConfig {
+    pub driver: Option<String>,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct Config {
     pub server: Option<ServerConfig>,
     pub environment: Option<String>,
     pub appkey: Option<String>,
     pub pg: Option<DatabaseConfig>,
+    pub session: Option<SessionConfig>,
+    pub mail: Option<MailConfig>,
 }
 
 impl Config {
@@ -34,6 +55,21 @@ impl Config {
             .try_deserialize()
     }
 
+    // how many access tokens a user may hold at once, the oldest are revoked past this
+    // at least one, the session that just signed in always has to survive
+    pub fn session_max_count(&self) -> i64 {
+        self.session.as_ref().and_then(|session| session.maxcount).unwrap_or(2).max(1)
+    }
+
+    // how long a device stays signed in through refresh tokens
+    pub fn session_ttl(&self) -> chrono::Duration {
+        chrono::Duration::days(self.session.as_ref().and_then(|session| session.ttldays).unwrap_or(7))
+    }
+
+    pub fn access_token_ttl(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.session.as_ref().and_then(|session| session.accessttlminutes).unwrap_or(15))
+    }
+
     pub fn database_url(&self) -> String {
         format!(
             "postgres://{}:{}@{}:{}/{}",
@@ -26,6 +26,21 @@ impl Errors {
     }
 }
 
+// "field: code" pairs, for places that report errors inside a message instead of a response
+impl std::fmt::Display for Errors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut field_errors: Vec<String> = self
+            .errors
+            .field_errors()
+            .into_iter()
+            .flat_map(|(field, errors)| errors.iter().map(move |error| format!("{}: {}", field, error.code)))
+            .collect();
+        field_errors.sort();
+
+        write!(f, "{}", field_errors.join(", "))
+    }
+}
+
 impl IntoResponse for Errors {
     fn into_response(self) -> Response {
         use validator::ValidationErrorsKind::Field;
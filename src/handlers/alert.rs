@@ -0,0 +1,31 @@
+use crate::models::branch::Branch;
+use crate::models::responses::DefaultResponse;
+use crate::models::specification::Specification;
+
+use axum::extract::{Path, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn low_stock(
+    State(db): State<PgPool>,
+    Path((branch_id,)): Path<(Uuid,)>,
+) -> Response {
+    let branch = Branch::get_by_id(&db, branch_id).await;
+
+    if branch.is_err() {
+        let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let specifications = Specification::get_low_stock_by_branch_id(&db, branch_id)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Get low stock specifications successfully")
+        .with_data(json!(specifications)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
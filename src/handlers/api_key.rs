@@ -0,0 +1,81 @@
+use crate::errors::FieldValidator;
+use crate::models::api_key::ApiKey;
+use crate::models::requests::api_key::RequestCreateApiKey;
+use crate::models::responses::DefaultResponse;
+use crate::token;
+
+use axum::extract::Path;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use axum::{extract::State, response::Json};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn get_by_branch_id(
+    State(db): State<PgPool>,
+    Path((branch_id,)): Path<(Uuid,)>,
+) -> Response {
+    let api_keys = ApiKey::get_by_branch_id(&db, branch_id).await.unwrap();
+
+    let body = DefaultResponse::ok("Get all api keys successfully")
+        .with_data(json!(api_keys)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn create(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+    Path((branch_id,)): Path<(Uuid,)>,
+    Json(payload): Json<RequestCreateApiKey>,
+) -> Response {
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let key = format!("sk_{}", token::generate());
+
+    let mut scopes = payload.scopes;
+    scopes.sort();
+    scopes.dedup();
+
+    let api_key = ApiKey::create(
+        &db,
+        branch_id,
+        payload.name,
+        key.chars().take(11).collect(),
+        token::digest(&key),
+        scopes,
+        user_id,
+    )
+    .await
+    .unwrap();
+
+    // the plain key is only ever returned here
+    let body = DefaultResponse::created("Create api key successfully")
+        .with_data(json!({ "api_key": api_key, "key": key })).into_json();
+
+    (StatusCode::CREATED, body).into_response()
+}
+
+pub async fn delete(
+    State(db): State<PgPool>,
+    Path((branch_id, api_key_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let api_key = match ApiKey::get_by_id(&db, branch_id, api_key_id).await {
+        Ok(api_key) => api_key,
+        Err(_) => {
+            let body = DefaultResponse::error("Api key not found", Some("Api key ID not found".to_string())).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    };
+
+    ApiKey::delete(&db, api_key.id).await.unwrap();
+
+    let body = DefaultResponse::ok("Revoke api key successfully").into_json();
+
+    (StatusCode::OK, body).into_response()
+}
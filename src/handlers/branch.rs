@@ -2,9 +2,14 @@ use std::str::FromStr;
 
 use crate::errors::{FieldValidator};
 use crate::models::branch::Branch;
+use crate::models::branch_member::BranchMember;
 use crate::models::product::Product;
-use crate::models::requests::branch::RequestFormBranch;
+use crate::models::requests::branch::{
+    RequestFormBranch, RequestFormCostingMethod, RequestFormRoundingPolicy,
+    RequestFormStockDeduction, RequestFormTwoFactorRoles,
+};
 use crate::models::responses::DefaultResponse;
+use crate::models::user::User;
 
 use axum::extract::Path;
 use axum::Extension;
@@ -49,6 +54,10 @@ pub async fn create(
         .await
         .unwrap();
 
+    BranchMember::create(&db, branch.id, user_id, String::from("OWNER"), None)
+        .await
+        .unwrap();
+
     let body = DefaultResponse::created("Create branch successfully")
         .with_data(json!(branch)).into_json();
 
@@ -112,6 +121,132 @@ pub async fn update(
     (StatusCode::OK, body).into_response()
 }
 
+pub async fn update_rounding_policy(
+    State(db): State<PgPool>,
+    Path((branch_id,)): Path<(Uuid,)>,
+    Json(payload): Json<RequestFormRoundingPolicy>,
+) -> Response {
+    let branch = Branch::get_by_id(&db, branch_id).await;
+
+    if branch.is_err() {
+        let body = DefaultResponse::error("Branch not found", Some("branch_id is not exist".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let branch = Branch::update_rounding_policy(&db, branch_id, payload.price_scale, payload.rounding_mode)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Update branch rounding policy successfully")
+        .with_data(json!(branch)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn update_costing_method(
+    State(db): State<PgPool>,
+    Path((branch_id,)): Path<(Uuid,)>,
+    Json(payload): Json<RequestFormCostingMethod>,
+) -> Response {
+    let branch = Branch::get_by_id(&db, branch_id).await;
+
+    if branch.is_err() {
+        let body = DefaultResponse::error("Branch not found", Some("branch_id is not exist".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let branch = Branch::update_costing_method(&db, branch_id, payload.costing_method)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Update branch costing method successfully")
+        .with_data(json!(branch)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn update_stock_deduction(
+    State(db): State<PgPool>,
+    Path((branch_id,)): Path<(Uuid,)>,
+    Json(payload): Json<RequestFormStockDeduction>,
+) -> Response {
+    let branch = Branch::get_by_id(&db, branch_id).await;
+
+    if branch.is_err() {
+        let body = DefaultResponse::error("Branch not found", Some("branch_id is not exist".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let branch = Branch::update_stock_deduction(&db, branch_id, payload.stock_deduction)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Update branch stock deduction successfully")
+        .with_data(json!(branch)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn update_two_factor_roles(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+    Path((branch_id,)): Path<(Uuid,)>,
+    Json(payload): Json<RequestFormTwoFactorRoles>,
+) -> Response {
+    let branch = Branch::get_by_id(&db, branch_id).await;
+
+    if branch.is_err() {
+        let body = DefaultResponse::error("Branch not found", Some("branch_id is not exist".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    // the change would lock the caller out of the branch on the very next request
+    let role = BranchMember::get_access(&db, branch_id, user_id)
+        .await
+        .unwrap()
+        .map(|access| access.role)
+        .unwrap_or_default();
+    let user = User::get_by_id(&db, user_id).await.unwrap();
+
+    if payload.two_factor_roles.contains(&role) && user.totp_enabled_at.is_none() {
+        let body = DefaultResponse::error("Two-factor not enabled", Some("Enable two-factor on your account before requiring it for your role".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let mut two_factor_roles = payload.two_factor_roles;
+    two_factor_roles.sort();
+    two_factor_roles.dedup();
+
+    let branch = Branch::update_two_factor_roles(&db, branch_id, two_factor_roles)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Update branch two factor roles successfully")
+        .with_data(json!(branch)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
 pub async fn get_by_id(
     State(db): State<PgPool>,
     Path((branch_id,)): Path<(Uuid,)>,
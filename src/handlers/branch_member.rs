@@ -0,0 +1,120 @@
+use crate::errors::FieldValidator;
+use crate::models::branch_member::BranchMember;
+use crate::models::requests::branch_member::{RequestInviteBranchMember, RequestUpdateBranchMember};
+use crate::models::responses::DefaultResponse;
+use crate::models::user::User;
+
+use axum::extract::Path;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use axum::{extract::State, response::Json};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn get_by_branch_id(
+    State(db): State<PgPool>,
+    Path((branch_id,)): Path<(Uuid,)>,
+) -> Response {
+    let branch_members = BranchMember::get_by_branch_id(&db, branch_id).await.unwrap();
+
+    let body = DefaultResponse::ok("Get all branch members successfully")
+        .with_data(json!(branch_members)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn invite(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+    Path((branch_id,)): Path<(Uuid,)>,
+    Json(payload): Json<RequestInviteBranchMember>,
+) -> Response {
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let user = match User::get_by_email(&db, payload.email).await {
+        Ok(user) => user,
+        Err(_) => {
+            let body = DefaultResponse::error("User not found", Some("No user registered with this email".to_string())).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    };
+
+    let branch_member = match BranchMember::create(&db, branch_id, user.id, payload.role, Some(user_id)).await {
+        Ok(branch_member) => branch_member,
+        Err(_) => {
+            let body = DefaultResponse::error("User is already a member", Some("User already belongs to this branch".to_string())).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    };
+
+    let body = DefaultResponse::created("Invite branch member successfully")
+        .with_data(json!(branch_member)).into_json();
+
+    (StatusCode::CREATED, body).into_response()
+}
+
+pub async fn update(
+    State(db): State<PgPool>,
+    Path((branch_id, branch_member_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<RequestUpdateBranchMember>,
+) -> Response {
+    let branch_member = match BranchMember::get_by_id(&db, branch_id, branch_member_id).await {
+        Ok(branch_member) => branch_member,
+        Err(_) => {
+            let body = DefaultResponse::error("Branch member not found", Some("Branch member ID not found".to_string())).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    };
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    if branch_member.role == "OWNER" && payload.role != "OWNER" && is_last_owner(&db, branch_id).await {
+        let body = DefaultResponse::error("Branch needs an owner", Some("Cannot demote the last owner of the branch".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let branch_member = BranchMember::update_role(&db, branch_member.id, payload.role)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Update branch member successfully")
+        .with_data(json!(branch_member)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn delete(
+    State(db): State<PgPool>,
+    Path((branch_id, branch_member_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let branch_member = match BranchMember::get_by_id(&db, branch_id, branch_member_id).await {
+        Ok(branch_member) => branch_member,
+        Err(_) => {
+            let body = DefaultResponse::error("Branch member not found", Some("Branch member ID not found".to_string())).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    };
+
+    if branch_member.role == "OWNER" && is_last_owner(&db, branch_id).await {
+        let body = DefaultResponse::error("Branch needs an owner", Some("Cannot remove the last owner of the branch".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    BranchMember::delete(&db, branch_member.id).await.unwrap();
+
+    let body = DefaultResponse::ok("Remove branch member successfully").into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+async fn is_last_owner(db: &PgPool, branch_id: Uuid) -> bool {
+    BranchMember::count_owners(db, branch_id).await.unwrap_or(0) <= 1
+}
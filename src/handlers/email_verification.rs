@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use crate::errors::FieldValidator;
+use crate::mailer::{self, Mail, Mailer};
+use crate::models::requests::user::RequestVerifyEmail;
+use crate::models::responses::DefaultResponse;
+use crate::models::user::User;
+use crate::models::user_token::UserToken;
+use crate::token;
+
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use axum::{extract::State, response::Json};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const VERIFICATION_TOKEN_TTL_HOURS: i64 = 24;
+
+pub async fn send_verification(
+    db: &PgPool,
+    mailer: Arc<dyn Mailer>,
+    user: &User,
+) -> Result<(), sqlx::Error> {
+    let verification_token = token::generate();
+    let expires_at = chrono::Utc::now().naive_utc() + chrono::Duration::hours(VERIFICATION_TOKEN_TTL_HOURS);
+
+    UserToken::create(db, user.id, "EMAIL_VERIFICATION", token::digest(&verification_token), expires_at).await?;
+
+    mailer::send_in_background(
+        mailer,
+        Mail {
+            to: user.email.clone(),
+            subject: String::from("Verify your email"),
+            body: format!(
+                "Hi {},\n\nUse this token to verify your email:\n\n{}\n\nIt expires in {} hours.",
+                user.name, verification_token, VERIFICATION_TOKEN_TTL_HOURS
+            ),
+        },
+    );
+
+    Ok(())
+}
+
+pub async fn verify(
+    State(db): State<PgPool>,
+    Json(payload): Json<RequestVerifyEmail>,
+) -> Response {
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    let user_token = match UserToken::consume(&mut db_transaction, "EMAIL_VERIFICATION", token::digest(&payload.token)).await {
+        Ok(user_token) => user_token,
+        Err(_) => {
+            db_transaction.rollback().await.unwrap();
+
+            let body = DefaultResponse::error("Verification token invalid", Some("token is not exist, expired or already used".to_string())).into_json();
+            return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+        }
+    };
+
+    let user = User::verify_email(&mut db_transaction, user_token.user_id)
+        .await
+        .unwrap();
+
+    db_transaction.commit().await.unwrap();
+
+    let body = DefaultResponse::ok("Verify email successfully")
+        .with_data(json!(user)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn resend(
+    State(db): State<PgPool>,
+    Extension(mailer): Extension<Arc<dyn Mailer>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Response {
+    let user = User::get_by_id(&db, user_id).await.unwrap();
+
+    if user.email_verified_at.is_some() {
+        let body = DefaultResponse::error("Email already verified", Some("email_verified_at is already set".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    send_verification(&db, mailer, &user).await.unwrap();
+
+    let body = DefaultResponse::ok("Verification email sent").into_json();
+
+    (StatusCode::OK, body).into_response()
+}
@@ -2,7 +2,6 @@ use std::io::Write;
 
 use axum::response::{IntoResponse, Response};
 use calamine::{open_workbook, Error, RangeDeserializerBuilder, Reader, Xlsx};
-use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use uuid::Uuid;
 
@@ -11,6 +10,7 @@ use crate::models::branch::Branch;
 use crate::models::product::Product;
 use crate::models::product_specification::ProductSpecification;
 use crate::models::specification::Specification;
+use crate::models::unit::Unit;
 use crate::{errors::Errors, models::responses::DefaultResponse};
 
 use axum::{extract::Multipart, extract::Path, extract::State};
@@ -21,8 +21,8 @@ use sqlx::PgPool;
 async fn process_specifications(
     db: &PgPool,
     db_transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
-    row: (String, String, String, i32, String, i32),
-    branch_id: Uuid,
+    row: (String, String, String, i32, String, Decimal),
+    branch: &Branch,
 ) -> Result<(), Errors> {
     let (_, name, unit, smallest_unit, unit_name, raw_price): (
         String,
@@ -30,16 +30,27 @@ async fn process_specifications(
         String,
         i32,
         String,
-        i32,
+        Decimal,
     ) = row;
+    let branch_id = branch.id;
 
     let name = name.to_lowercase();
     let unit_name = unit_name.to_lowercase();
 
-    let lowest_price = (Decimal::from(raw_price) / Decimal::from(smallest_unit))
-        .round_dp(2)
-        .to_f64()
-        .expect("failed to convert to f64");
+    let lowest_price = match raw_price.checked_div(Decimal::from(smallest_unit)) {
+        Some(lowest_price) => branch.round_price(lowest_price),
+        None => {
+            return Err(Errors::new(&[(
+                "smallest_unit",
+                "failed to generate lowest price",
+            )]))
+        }
+    };
+
+    let unit_id = match Unit::get_by_code_or_name(db, &branch_id, &unit_name).await {
+        Ok(unit) => Some(unit.id),
+        Err(_) => return Err(Errors::new(&[("unit_name", "unit is not registered")])),
+    };
 
     let specification = Specification::get_by_name_and_branch_id(&db, &name, &branch_id).await;
 
@@ -53,6 +64,7 @@ async fn process_specifications(
             &unit,
             &lowest_price,
             &raw_price,
+            &unit_id,
         )
         .await;
 
@@ -72,6 +84,7 @@ async fn process_specifications(
             unit,
             lowest_price,
             raw_price,
+            unit_id,
         )
         .await
         {
@@ -95,7 +108,7 @@ async fn process_product_specifications(
     db_transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     product_name: &String,
     specification_name: String,
-    measure: i32,
+    measure: Decimal,
 
     branch_id: Uuid,
 ) -> Result<(), Errors> {
@@ -225,8 +238,11 @@ pub async fn product_specifications(
 
     let mut db_transaction = db.begin().await.expect("Failed to begin transaction");
 
+    // sheet row of the first specification, right below the header
+    let mut row_number = range_specifications.start().map(|(row, _)| row + 2).unwrap_or(2);
+
     while let Some(row) = iter.next() {
-        match process_specifications(&db, &mut db_transaction, row.unwrap(), branch_id).await {
+        match process_specifications(&db, &mut db_transaction, row.unwrap(), branch.as_ref().unwrap()).await {
             Ok(_) => (),
             Err(e) => {
                 Logger::new(format!("{:?}", e)).log();
@@ -234,11 +250,16 @@ pub async fn product_specifications(
                     .rollback()
                     .await
                     .expect("Failed to rollback transaction");
-                let body =
-                    DefaultResponse::error("Failed to import specification", None).into_json();
+                let body = DefaultResponse::error(
+                    "Failed to import specification",
+                    Some(format!("Specifications row {}: {}", row_number, e)),
+                )
+                .into_json();
                 return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
             }
         }
+
+        row_number += 1;
     }
 
     match db_transaction.commit().await {
@@ -278,7 +299,7 @@ pub async fn product_specifications(
     let mut active_product_name = String::new();
 
     while let Some(row) = iter.next() {
-        let (product_name, specification_name, measure, _): (String, String, i32, String) =
+        let (product_name, specification_name, measure, _): (String, String, Decimal, String) =
             row.unwrap();
 
         if !product_name.is_empty() {
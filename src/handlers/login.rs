@@ -1,20 +1,43 @@
+use std::net::{IpAddr, SocketAddr};
+
+use crate::config::Config;
 use crate::errors::{Errors, FieldValidator};
 use crate::models::oauth_access_token::OauthAccessToken;
-use crate::models::requests::login::RequestLogin;
+use crate::models::oauth_refresh_token::OauthRefreshToken;
+use crate::handlers::two_factor;
+use crate::logger::Logger;
+use crate::models::login_attempt::LoginAttempt;
+use crate::models::requests::login::{RequestLogin, RequestLoginTwoFactor};
 use crate::models::responses::DefaultResponse;
 use crate::models::user::User;
+use crate::models::user_token::UserToken;
 
-use argon2::{self, Config};
-use argon2::{ThreadMode, Variant, Version};
+use crate::password;
+use crate::token;
 
+use axum::extract::ConnectInfo;
+use axum::http::{header, HeaderMap};
 use axum::response::{IntoResponse, Response};
 use axum::{extract::State, response::Json};
-use crypto_hash::{hex_digest, Algorithm};
 use reqwest::StatusCode;
 use serde_json::{json};
 use sqlx::PgPool;
 
-pub async fn login(State(db): State<PgPool>, Json(payload): Json<RequestLogin>) -> Response {
+const TWO_FACTOR_TOKEN_TTL_MINUTES: i64 = 5;
+
+// failures allowed before the backoff starts and before the flat lockout, per email and per ip
+const EMAIL_FREE_ATTEMPTS: i64 = 3;
+const EMAIL_LOCKOUT_ATTEMPTS: i64 = 10;
+const IP_FREE_ATTEMPTS: i64 = 10;
+const IP_LOCKOUT_ATTEMPTS: i64 = 50;
+const LOCKOUT_SECONDS: i64 = 15 * 60;
+
+pub async fn login(
+    State(db): State<PgPool>,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<RequestLogin>,
+) -> Response {
     let mut extractor = FieldValidator::validate(&payload);
 
     let email = extractor.extract("email", Some(payload.email));
@@ -24,74 +47,306 @@ pub async fn login(State(db): State<PgPool>, Json(payload): Json<RequestLogin>)
         Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
     };
         
-    let salt = std::env::var("APPKEY").unwrap();
-    let config = Config {
-        variant: Variant::Argon2i,
-        version: Version::Version13,
-        mem_cost: 512,
-        time_cost: 2,
-        lanes: 1,
-        thread_mode: ThreadMode::Parallel,
-        secret: &[],
-        ad: &[],
-        hash_length: 32,
-    };
-
     let email = email.trim().to_string().to_lowercase();
-    let password = password.trim().to_string().to_lowercase();
-    let hash = argon2::hash_encoded(password.as_bytes(), salt.as_bytes(), &config).unwrap();
+    let ip_address = client_ip(&headers, remote_addr);
+    let user_agent = user_agent(&headers);
+
+    match get_retry_after(&db, &email, &ip_address).await {
+        Ok(None) => (),
+        Ok(Some(retry_after)) => {
+            record_attempt(&db, &email, None, &ip_address, &user_agent, "LOCKED").await;
 
-    let matches = argon2::verify_encoded(&hash, password.as_bytes()).unwrap();
-    assert!(matches);
+            let body = DefaultResponse::error("Too many login attempts", Some(format!("Try again in {} seconds", retry_after))).into_json();
 
-    let user = match User::login(&db, email, hash).await {
-        Ok(user) => user,
+            return (StatusCode::TOO_MANY_REQUESTS, [(header::RETRY_AFTER, retry_after.to_string())], body).into_response();
+        }
         Err(err) => {
-            let body = DefaultResponse::error("Login failed", Some(err.to_string())).into_json();
+            Logger::new(format!("{:?}", err)).log();
+
+            let body = DefaultResponse::error("Login failed", Some("Something went wrong, try again later".to_string())).into_json();
+
+            return (StatusCode::INTERNAL_SERVER_ERROR, body).into_response();
+        }
+    }
+
+    let user = User::get_by_email(&db, email.clone()).await;
+
+    let password_matched = match &user {
+        Ok(user) => password::verify(&user.password, &password).await,
+        Err(_) => {
+            // hash anyway so an unknown email takes as long as a wrong password
+            password::hash(&password).await;
+            false
+        }
+    };
+
+    let user = match user {
+        Ok(user) if password_matched => user,
+        user => {
+            let user_id = user.ok().map(|user| user.id);
+
+            record_attempt(&db, &email, user_id, &ip_address, &user_agent, "FAILED").await;
+
+            let body = DefaultResponse::error("Login failed", Some("Email or password is incorrect".to_string())).into_json();
 
             return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
         }
     };
 
-    let token = match set_access_token(&db, &user, &salt).await {
-        Ok(token) => token,
+    // move users still on the APPKEY salted hash to their own salt now that we know the password
+    let user = if password::is_legacy(&user.password) {
+        let mut db_transaction = db.begin().await.unwrap();
+
+        match User::update_password(&mut db_transaction, user.id, password::hash(&password).await).await {
+            Ok(user) => {
+                db_transaction.commit().await.unwrap();
+                user
+            }
+            Err(_) => {
+                db_transaction.rollback().await.unwrap();
+                user
+            }
+        }
+    } else {
+        user
+    };
+
+    // the password is right, the session waits for the second factor
+    if user.totp_enabled_at.is_some() {
+        let two_factor_token = token::generate();
+        let expires_at = chrono::Utc::now().naive_utc() + chrono::Duration::minutes(TWO_FACTOR_TOKEN_TTL_MINUTES);
+
+        UserToken::create(&db, user.id, "TWO_FACTOR_LOGIN", token::digest(&two_factor_token), expires_at)
+            .await
+            .unwrap();
+
+        record_attempt(&db, &email, Some(user.id), &ip_address, &user_agent, "TWO_FACTOR_REQUIRED").await;
+
+        let body = DefaultResponse::ok("Two-factor code required")
+            .with_data(json!({ "two_factor_required": true, "two_factor_token": two_factor_token })).into_json();
+
+        return (StatusCode::OK, body).into_response();
+    }
+
+    let (access_token, refresh_token) = match set_access_token(&db, &user, user_agent.clone()).await {
+        Ok(tokens) => tokens,
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    record_attempt(&db, &email, Some(user.id), &ip_address, &user_agent, "SUCCESS").await;
+
+    let body = DefaultResponse::ok("Login successfully")
+        .with_access_token(access_token)
+        .with_refresh_token(refresh_token)
+        .with_data(json!(user)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn login_two_factor(
+    State(db): State<PgPool>,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<RequestLoginTwoFactor>,
+) -> Response {
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    // one attempt per password check, a wrong code sends the user back to the password step
+    let mut db_transaction = db.begin().await.unwrap();
+
+    let user_token = match UserToken::consume(&mut db_transaction, "TWO_FACTOR_LOGIN", token::digest(&payload.two_factor_token)).await {
+        Ok(user_token) => user_token,
+        Err(_) => {
+            db_transaction.rollback().await.unwrap();
+
+            let body = DefaultResponse::error("Login failed", Some("two_factor_token is not exist, expired or already used".to_string())).into_json();
+            return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+        }
+    };
+
+    db_transaction.commit().await.unwrap();
+
+    let user = User::get_by_id(&db, user_token.user_id).await.unwrap();
+    let ip_address = client_ip(&headers, remote_addr);
+    let user_agent = user_agent(&headers);
+
+    // wrong codes count towards the backoff of the email, the next password step gets slowed down
+    if !two_factor::check_code(&db, &user, &payload.code).await {
+        record_attempt(&db, &user.email, Some(user.id), &ip_address, &user_agent, "TWO_FACTOR_FAILED").await;
+
+        let body = DefaultResponse::error("Login failed", Some("Two-factor code is incorrect".to_string())).into_json();
+
+        return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+    }
+
+    let (access_token, refresh_token) = match set_access_token(&db, &user, user_agent.clone()).await {
+        Ok(tokens) => tokens,
         Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
     };
 
+    record_attempt(&db, &user.email, Some(user.id), &ip_address, &user_agent, "SUCCESS").await;
+
     let body = DefaultResponse::ok("Login successfully")
-        .with_access_token(token.access_token)
+        .with_access_token(access_token)
+        .with_refresh_token(refresh_token)
         .with_data(json!(user)).into_json();
 
     (StatusCode::OK, body).into_response()
 }
 
+// seconds until the email or the ip may try again, whichever is longer
+async fn get_retry_after(db: &PgPool, email: &str, ip_address: &str) -> Result<Option<i64>, sqlx::Error> {
+    let by_email = LoginAttempt::get_recent_failures_by_email(db, email.to_string())
+        .await?
+        .retry_after(EMAIL_FREE_ATTEMPTS, EMAIL_LOCKOUT_ATTEMPTS, LOCKOUT_SECONDS);
+
+    let by_ip_address = LoginAttempt::get_recent_failures_by_ip_address(db, ip_address.to_string())
+        .await?
+        .retry_after(IP_FREE_ATTEMPTS, IP_LOCKOUT_ATTEMPTS, LOCKOUT_SECONDS);
+
+    Ok(by_email.max(by_ip_address))
+}
+
+// the audit trail shouldn't decide whether a login goes through, failures only end up in the log
+async fn record_attempt(
+    db: &PgPool,
+    email: &str,
+    user_id: Option<uuid::Uuid>,
+    ip_address: &str,
+    user_agent: &Option<String>,
+    status: &str,
+) {
+    if let Err(err) = LoginAttempt::create(
+        db,
+        email.chars().take(255).collect(),
+        user_id,
+        ip_address.to_string(),
+        user_agent.clone(),
+        status,
+    )
+    .await
+    {
+        Logger::new(format!("failed to record login attempt: {:?}", err)).log();
+    }
+}
+
+// X-Forwarded-For is only trusted from the reverse proxy on the private network, which appends the
+// address it saw as the last entry, anything before that came from the client
+fn client_ip(headers: &HeaderMap, remote_addr: SocketAddr) -> String {
+    let is_proxy = match remote_addr.ip() {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_private(),
+        IpAddr::V6(ip) => ip.is_loopback(),
+    };
+
+    let forwarded_for = headers
+        .get("X-Forwarded-For")
+        .and_then(|forwarded_for| forwarded_for.to_str().ok())
+        .and_then(|forwarded_for| forwarded_for.rsplit(',').next())
+        .and_then(|ip| ip.trim().parse::<IpAddr>().ok());
+
+    match forwarded_for {
+        Some(ip) if is_proxy => ip.to_string(),
+        _ => remote_addr.ip().to_string(),
+    }
+}
+
+fn user_agent(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::USER_AGENT)
+        .and_then(|user_agent| user_agent.to_str().ok())
+        .map(|user_agent| user_agent.chars().take(255).collect::<String>())
+}
+
 async fn set_access_token(
     db: &PgPool,
     user: &User,
-    salt: &String,
-) -> Result<OauthAccessToken, Errors> {
-    let timestamp = chrono::Utc::now().timestamp_millis().to_string();
+    user_agent: Option<String>,
+) -> Result<(String, String), Errors> {
+    let config = Config::from_env().unwrap();
+    let now = chrono::Utc::now().naive_utc();
+
+    // make room for the new token within the session limit
+    if OauthAccessToken::revoke_oldest_by_user_id(db, user.id, config.session_max_count() - 1)
+        .await
+        .is_err()
+    {
+        return Err(Errors::new(&[("token generation", "failed to revoke old tokens")]));
+    }
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    let session_expires_at = now + config.session_ttl();
+
+    let access_token = token::generate();
 
-    let payload = format!("{}/{}/{}/", &user.id, &salt, timestamp);
+    let token = match OauthAccessToken::create(
+        &mut db_transaction,
+        token::digest(&access_token),
+        user.id,
+        user_agent,
+        std::cmp::min(now + config.access_token_ttl(), session_expires_at),
+        session_expires_at,
+    )
+    .await
+    {
+        Ok(token) => token,
+        Err(_) => return Err(Errors::new(&[("token generation", "failed")])),
+    };
 
-    let max_access_token_count = 2; // Only allow 2 access tokens per user
-    let access_token = hex_digest(Algorithm::SHA256, payload.as_bytes());
+    let refresh_token = token::generate();
 
-    let count_access_token = OauthAccessToken::get_count_by_user_id(&db, user.id)
+    if OauthRefreshToken::create(&mut db_transaction, token.id, token::digest(&refresh_token), session_expires_at)
         .await
-        .unwrap();
+        .is_err()
+    {
+        return Err(Errors::new(&[("token generation", "failed to create refresh token")]));
+    }
 
-    if count_access_token >= max_access_token_count {
-        let token = OauthAccessToken::get_by_user_id(&db, user.id)
-            .await
-            .expect("failed to get tokens");
-        OauthAccessToken::delete(&db, token.access_token)
-            .await
-            .expect("failed to delete token");
+    db_transaction.commit().await.unwrap();
+
+    Ok((access_token, refresh_token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forwarded_for(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Forwarded-For", value.parse().unwrap());
+        headers
     }
 
-    match OauthAccessToken::create(&db, access_token, user.id).await {
-        Ok(token) => Ok(token),
-        Err(_) => return Err(Errors::new(&[("token generation", "failed")])),
+    #[test]
+    fn client_ip_uses_peer_without_header() {
+        let remote_addr: SocketAddr = "10.0.0.2:50000".parse().unwrap();
+
+        assert_eq!(client_ip(&HeaderMap::new(), remote_addr), "10.0.0.2");
+    }
+
+    #[test]
+    fn client_ip_takes_last_forwarded_entry_from_proxy() {
+        let headers = forwarded_for("1.1.1.1, 203.0.113.7");
+
+        assert_eq!(client_ip(&headers, "10.0.0.2:50000".parse().unwrap()), "203.0.113.7");
+        assert_eq!(client_ip(&headers, "127.0.0.1:50000".parse().unwrap()), "203.0.113.7");
+        assert_eq!(client_ip(&headers, "[::1]:50000".parse().unwrap()), "203.0.113.7");
+    }
+
+    #[test]
+    fn client_ip_ignores_header_from_public_peer() {
+        let headers = forwarded_for("203.0.113.7");
+
+        assert_eq!(client_ip(&headers, "198.51.100.4:50000".parse().unwrap()), "198.51.100.4");
+    }
+
+    #[test]
+    fn client_ip_ignores_invalid_forwarded_entry() {
+        let headers = forwarded_for("203.0.113.7, not-an-ip");
+
+        assert_eq!(client_ip(&headers, "10.0.0.2:50000".parse().unwrap()), "10.0.0.2");
     }
 }
@@ -6,4 +6,19 @@ pub mod specification;
 pub mod product;
 pub mod specification_history;
 pub mod transaction;
-pub mod import;
\ No newline at end of file
+pub mod import;
+pub mod opname;
+pub mod stock_balance;
+pub mod alert;
+pub mod supplier;
+pub mod purchase_order;
+pub mod unit;
+pub mod specification_recipe;
+pub mod production;
+pub mod branch_member;
+pub mod session;
+pub mod token;
+pub mod api_key;
+pub mod password;
+pub mod email_verification;
+pub mod two_factor;
\ No newline at end of file
@@ -0,0 +1,296 @@
+use crate::errors::FieldValidator;
+use crate::logger::Logger;
+use crate::models::branch::Branch;
+use crate::models::opname::{Opname, OpnameItem};
+use crate::models::requests::opname::{RequestCreateOpname, RequestUpdateOpnameItems};
+use crate::models::responses::DefaultResponse;
+use crate::models::specification_history::SpecificationHistory;
+use crate::models::stock_balance::StockBalance;
+
+use axum::extract::Path;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use axum::{extract::State, response::Json};
+use reqwest::StatusCode;
+use rust_decimal::Decimal;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn create(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+    Path((branch_id,)): Path<(Uuid,)>,
+    Json(payload): Json<RequestCreateOpname>,
+) -> Response {
+    let branch = Branch::get_by_id(&db, branch_id).await;
+
+    if branch.is_err() {
+        let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    if Opname::get_open_by_branch_id(&db, branch_id).await.is_ok() {
+        let body = DefaultResponse::error("Opname already open", Some("Close the running opname first".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    let opname = match Opname::create(&mut db_transaction, branch_id, user_id, payload.note).await {
+        Ok(opname) => opname,
+        // another open slipped in between the check above and the insert
+        Err(sqlx::Error::Database(err)) if err.code().as_deref() == Some("23505") => {
+            let body = DefaultResponse::error("Opname already open", Some("Close the running opname first".to_string())).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+        Err(err) => {
+            Logger::new(format!("{:?}", err)).log();
+
+            let body = DefaultResponse::error("Something went wrong", Some("Failed to create opname".to_string())).into_json();
+            return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+        }
+    };
+
+    if let Err(err) = OpnameItem::create_snapshot(&mut db_transaction, opname.id, branch_id).await {
+        Logger::new(format!("{:?}", err)).log();
+
+        let body = DefaultResponse::error("Something went wrong", Some("Failed to snapshot specifications".to_string())).into_json();
+        return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+    }
+
+    let commit = db_transaction.commit().await;
+
+    if commit.is_err() {
+        let body = DefaultResponse::error("Something went wrong", Some("Failed to commit db_transaction".to_string())).into_json();
+        return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+    }
+
+    let opname = Opname::get_by_id_with_items(&db, branch_id, opname.id)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::created("Create opname successfully")
+        .with_data(json!(opname)).into_json();
+
+    (StatusCode::CREATED, body).into_response()
+}
+
+pub async fn get_by_branch_id(
+    State(db): State<PgPool>,
+    Path((branch_id,)): Path<(Uuid,)>,
+) -> Response {
+    let branch = Branch::get_by_id(&db, branch_id).await;
+
+    if branch.is_err() {
+        let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let opnames = Opname::get_by_branch_id(&db, branch_id).await.unwrap();
+
+    let body = DefaultResponse::ok("Get all opnames successfully")
+        .with_data(json!(opnames)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn get_by_id(
+    State(db): State<PgPool>,
+    Path((branch_id, opname_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let opname = Opname::get_by_id_with_items(&db, branch_id, opname_id).await;
+
+    if opname.is_err() {
+        let body = DefaultResponse::error("Opname not found", Some("Opname ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let body = DefaultResponse::ok("Get opname successfully")
+        .with_data(json!(opname.unwrap())).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn update_items(
+    State(db): State<PgPool>,
+    Path((branch_id, opname_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<RequestUpdateOpnameItems>,
+) -> Response {
+    let opname = Opname::get_by_id_with_items(&db, branch_id, opname_id).await;
+
+    if opname.is_err() {
+        let body = DefaultResponse::error("Opname not found", Some("Opname ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    if opname.unwrap().status != "OPEN" {
+        let body = DefaultResponse::error("Opname already closed", Some("Counted quantity can only be set on open opname".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    for item in payload.items.iter() {
+        match FieldValidator::validate(item).check() {
+            Ok(_) => (),
+            Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+        };
+    }
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    for item in payload.items.iter() {
+        let result = OpnameItem::update_counted_quantity(
+            &mut db_transaction,
+            opname_id,
+            item.specification_id,
+            item.counted_quantity,
+        )
+        .await;
+
+        if result.is_err() {
+            db_transaction
+                .rollback()
+                .await
+                .expect("Failed to rollback transaction");
+
+            let body = DefaultResponse::error("Specification not found", Some(format!("Specification {} is not part of this opname", item.specification_id))).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    }
+
+    let commit = db_transaction.commit().await;
+
+    if commit.is_err() {
+        let body = DefaultResponse::error("Something went wrong", Some("Failed to commit db_transaction".to_string())).into_json();
+        return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+    }
+
+    let opname = Opname::get_by_id_with_items(&db, branch_id, opname_id)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Update opname items successfully")
+        .with_data(json!(opname)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+// close the opname and post one adjustment history per variance so the ledger matches the count, the variance
+// is taken against the balance at close so movements posted while the count was open are kept
+pub async fn close(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+    Path((branch_id, opname_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let opname = Opname::get_by_id_with_items(&db, branch_id, opname_id).await;
+
+    if opname.is_err() {
+        let body = DefaultResponse::error("Opname not found", Some("Opname ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let branch = Branch::get_by_id(&db, branch_id).await.unwrap();
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    if Opname::close(&mut db_transaction, opname_id, user_id).await.is_err() {
+        let body = DefaultResponse::error("Opname already closed", Some("Opname status is not OPEN".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let counted_items = OpnameItem::get_counted_by_opname_id(&mut db_transaction, opname_id)
+        .await
+        .unwrap();
+
+    for item in counted_items {
+        let current_quantity = match StockBalance::get_by_specification_id_for_update(&mut db_transaction, item.specification_id).await {
+            Ok(stock_balance) => stock_balance.map_or(Decimal::ZERO, |stock_balance| stock_balance.quantity),
+            Err(err) => {
+                Logger::new(format!("{:?}", err)).log();
+
+                db_transaction
+                    .rollback()
+                    .await
+                    .expect("Failed to rollback transaction");
+
+                let body = DefaultResponse::error("Something went wrong", Some("Failed to read stock balance".to_string())).into_json();
+                return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+            }
+        };
+
+        if let Err(err) = OpnameItem::update_system_quantity(&mut db_transaction, item.id, current_quantity).await {
+            Logger::new(format!("{:?}", err)).log();
+
+            db_transaction
+                .rollback()
+                .await
+                .expect("Failed to rollback transaction");
+
+            let body = DefaultResponse::error("Something went wrong", Some("Failed to update opname item".to_string())).into_json();
+            return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+        }
+
+        let variance = item.counted_quantity.unwrap_or(current_quantity) - current_quantity;
+
+        if variance.is_zero() {
+            continue;
+        }
+
+        let flow_type = if variance.is_sign_positive() { "IN" } else { "OUT" };
+        let quantity = variance.abs();
+
+        let price = branch.round_price(quantity * item.unit_price);
+
+        let result = SpecificationHistory::create(
+            &mut db_transaction,
+            item.specification_id,
+            None,
+            user_id,
+            Some(format!("Opname adjustment {}", opname_id)),
+            String::from(flow_type),
+            quantity,
+            price,
+            item.unit_price,
+            None,
+        )
+        .await;
+
+        if let Err(err) = result {
+            Logger::new(format!("{:?}", err)).log();
+
+            db_transaction
+                .rollback()
+                .await
+                .expect("Failed to rollback transaction");
+
+            let body = DefaultResponse::error("Something went wrong", Some("Failed to post opname adjustment".to_string())).into_json();
+            return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+        }
+    }
+
+    let commit = db_transaction.commit().await;
+
+    if commit.is_err() {
+        let body = DefaultResponse::error("Something went wrong", Some("Failed to commit db_transaction".to_string())).into_json();
+        return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+    }
+
+    let opname = Opname::get_by_id_with_items(&db, branch_id, opname_id)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Close opname successfully")
+        .with_data(json!(opname)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
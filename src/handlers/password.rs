@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use crate::errors::FieldValidator;
+use crate::mailer::{self, Mail, Mailer};
+use crate::models::oauth_access_token::OauthAccessToken;
+use crate::models::requests::user::{RequestForgotPassword, RequestResetPassword};
+use crate::models::responses::DefaultResponse;
+use crate::models::user::User;
+use crate::models::user_token::UserToken;
+use crate::password;
+use crate::token;
+
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use axum::{extract::State, response::Json};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+
+const RESET_TOKEN_TTL_MINUTES: i64 = 60;
+
+pub async fn forgot(
+    State(db): State<PgPool>,
+    Extension(mailer): Extension<Arc<dyn Mailer>>,
+    Json(payload): Json<RequestForgotPassword>,
+) -> Response {
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let email = payload.email.trim().to_string().to_lowercase();
+
+    // same answer whether the email is registered or not, so this can't be used to probe for accounts
+    if let Ok(user) = User::get_by_email(&db, email).await {
+        let reset_token = token::generate();
+        let expires_at = chrono::Utc::now().naive_utc() + chrono::Duration::minutes(RESET_TOKEN_TTL_MINUTES);
+
+        UserToken::create(&db, user.id, "PASSWORD_RESET", token::digest(&reset_token), expires_at)
+            .await
+            .unwrap();
+
+        mailer::send_in_background(
+            mailer,
+            Mail {
+                to: user.email,
+                subject: String::from("Reset your password"),
+                body: format!(
+                    "Hi {},\n\nUse this token to reset your password:\n\n{}\n\nIt expires in {} minutes and can only be used once. If you didn't ask for a reset you can ignore this email.",
+                    user.name, reset_token, RESET_TOKEN_TTL_MINUTES
+                ),
+            },
+        );
+    }
+
+    let body = DefaultResponse::ok("If the email is registered, a reset token has been sent").into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn reset(
+    State(db): State<PgPool>,
+    Json(payload): Json<RequestResetPassword>,
+) -> Response {
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    let user_token = match UserToken::consume(&mut db_transaction, "PASSWORD_RESET", token::digest(&payload.token)).await {
+        Ok(user_token) => user_token,
+        Err(_) => {
+            db_transaction.rollback().await.unwrap();
+
+            let body = DefaultResponse::error("Reset token invalid", Some("token is not exist, expired or already used".to_string())).into_json();
+            return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+        }
+    };
+
+    // the token only counts as used once the new password is stored
+    if User::update_password(&mut db_transaction, user_token.user_id, password::hash(&payload.password).await)
+        .await
+        .is_err()
+    {
+        db_transaction.rollback().await.unwrap();
+
+        let body = DefaultResponse::error("Reset password failed", Some("failed to update password".to_string())).into_json();
+        return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+    }
+
+    db_transaction.commit().await.unwrap();
+
+    // whoever had the old password shouldn't stay signed in
+    OauthAccessToken::revoke_oldest_by_user_id(&db, user_token.user_id, 0)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Reset password successfully").into_json();
+
+    (StatusCode::OK, body).into_response()
+}
@@ -1,9 +1,10 @@
-use crate::errors::FieldValidator;
+use crate::errors::{Errors, FieldValidator};
 use crate::models::product::Product;
 use crate::models::product_specification::ProductSpecification;
 use crate::models::requests::product::RequestCreateProductSpecification;
 use crate::models::responses::DefaultResponse;
 use crate::models::specification::Specification;
+use crate::models::unit::Unit;
 
 use axum::extract::Path;
 use axum::response::{IntoResponse, Response};
@@ -32,28 +33,39 @@ pub async fn get_all(State(db): State<PgPool>, Path((branch_id,)): Path<(Uuid,)>
 
 pub async fn set_product_specification(
     State(db): State<PgPool>,
-    Path((_,)): Path<(Uuid,)>,
+    Path((branch_id,)): Path<(Uuid,)>,
     Json(payload): Json<RequestCreateProductSpecification>,
 ) -> Response {
     let mut extractor = FieldValidator::validate(&payload);
 
     let quantity = extractor.extract("quantity", Some(payload.quantity));
-    extractor.check();
+    match extractor.check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
 
     let product = Product::get_by_id(&db, payload.product_id).await;
 
-    if product.is_err() {
+    if product.is_err() || product.unwrap().branch_id != branch_id {
         let body = DefaultResponse::error("Product not found", Some("product_id is not exist".to_string())).into_json();
             return (StatusCode::BAD_REQUEST, body).into_response();
     }
 
     let specification = Specification::get_by_id(&db, payload.specification_id).await;
 
-    if specification.is_err() {
+    if specification.is_err() || specification.as_ref().unwrap().branch_id != branch_id {
         let body = DefaultResponse::error("Specification not found", Some("specification_id is not exist".to_string())).into_json();
         return (StatusCode::BAD_REQUEST, body).into_response();
     }
 
+    let quantity = match &payload.unit {
+        Some(unit) => match Unit::convert_to_specification_unit(&db, specification.as_ref().unwrap(), unit, quantity).await {
+            Ok(quantity) => quantity,
+            Err(code) => return (StatusCode::UNPROCESSABLE_ENTITY, Errors::new(&[("unit", code)]).into_response()).into_response(),
+        },
+        None => quantity,
+    };
+
     let product_specification = ProductSpecification::get_by_product_and_specification(
         &db,
         payload.product_id,
@@ -0,0 +1,289 @@
+use crate::errors::{Errors, FieldValidator};
+use crate::logger::Logger;
+use crate::models::branch::Branch;
+use crate::models::production::{Production, ProductionItem};
+use crate::models::requests::production::RequestCreateProduction;
+use crate::models::responses::DefaultResponse;
+use crate::models::specification::Specification;
+use crate::models::specification_history::SpecificationHistory;
+use crate::models::specification_recipe::SpecificationRecipe;
+use crate::models::stock_balance::StockBalance;
+
+use axum::extract::Path;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use axum::{extract::State, response::Json};
+use reqwest::StatusCode;
+use rust_decimal::Decimal;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn create(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+    Path((branch_id,)): Path<(Uuid,)>,
+    Json(payload): Json<RequestCreateProduction>,
+) -> Response {
+    let branch = match Branch::get_by_id(&db, branch_id).await {
+        Ok(branch) => branch,
+        Err(_) => {
+            let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    };
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let specification = match Specification::get_by_id(&db, payload.specification_id).await {
+        Ok(specification) if specification.branch_id == branch_id => specification,
+        _ => {
+            let body = DefaultResponse::error("Specification not found", Some("Specification ID not found".to_string())).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    };
+
+    if !specification.is_prepared || specification.recipe_yield.is_none() {
+        let body = DefaultResponse::error("Specification cannot be produced", Some("Only prepared specifications with a recipe can be produced".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    let production = match process_create(&mut db_transaction, &branch, &specification, user_id, &payload).await {
+        Ok(production) => production,
+        Err(err) => {
+            Logger::new(format!("{:?}", err)).log();
+
+            db_transaction
+                .rollback()
+                .await
+                .expect("Failed to rollback transaction");
+
+            return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response();
+        }
+    };
+
+    let commit = db_transaction.commit().await;
+
+    if commit.is_err() {
+        let body = DefaultResponse::error("Something went wrong", Some("Failed to commit db_transaction".to_string())).into_json();
+        return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+    }
+
+    let production = Production::get_by_id_with_items(&db, branch_id, production.id)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::created("Create production successfully")
+        .with_data(json!(production)).into_json();
+
+    (StatusCode::CREATED, body).into_response()
+}
+
+pub async fn get_by_branch_id(
+    State(db): State<PgPool>,
+    Path((branch_id,)): Path<(Uuid,)>,
+) -> Response {
+    let branch = Branch::get_by_id(&db, branch_id).await;
+
+    if branch.is_err() {
+        let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let productions = Production::get_by_branch_id(&db, branch_id).await.unwrap();
+
+    let body = DefaultResponse::ok("Get all productions successfully")
+        .with_data(json!(productions)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn get_by_id(
+    State(db): State<PgPool>,
+    Path((branch_id, production_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let production = Production::get_by_id_with_items(&db, branch_id, production_id).await;
+
+    if production.is_err() {
+        let body = DefaultResponse::error("Production not found", Some("Production ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let body = DefaultResponse::ok("Get production successfully")
+        .with_data(json!(production.unwrap())).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+// post OUT histories for everything the batch consumed and one IN history for the prepared output,
+// the output is valued at the branch costing method cost of what went into it
+async fn process_create(
+    db_transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    branch: &Branch,
+    specification: &Specification,
+    created_by: Uuid,
+    payload: &RequestCreateProduction,
+) -> Result<Production, Errors> {
+    let production = match Production::create(
+        db_transaction,
+        branch.id,
+        specification.id,
+        created_by,
+        payload.quantity,
+        payload.note.to_owned(),
+    )
+    .await
+    {
+        Ok(production) => production,
+        Err(_) => {
+            return Err(Errors::new(&[(
+                "production",
+                "failed to create production",
+            )]))
+        }
+    };
+
+    let components = match SpecificationRecipe::get_by_specification_id(db_transaction, specification.id).await {
+        Ok(components) => components,
+        Err(_) => {
+            return Err(Errors::new(&[(
+                "specification_recipe",
+                "failed to get specification recipe",
+            )]))
+        }
+    };
+
+    let recipe_yield = specification.recipe_yield.unwrap_or(Decimal::ONE);
+    let note = Some(format!("Production {}", production.id));
+    let mut total_cost = Decimal::ZERO;
+
+    for component in components {
+        let component_quantity = payload.quantity * component.quantity / recipe_yield;
+
+        let exploded_specifications = match SpecificationRecipe::explode(
+            db_transaction,
+            component.component_specification_id,
+            component_quantity,
+        )
+        .await
+        {
+            Ok(exploded_specifications) => exploded_specifications,
+            Err(_) => {
+                return Err(Errors::new(&[(
+                    "specification_recipe",
+                    "failed to explode specification recipe",
+                )]))
+            }
+        };
+
+        for exploded_specification in exploded_specifications {
+            let cost = match StockBalance::get_out_cost(
+                db_transaction,
+                &branch.costing_method,
+                exploded_specification.specification_id,
+                exploded_specification.quantity,
+            )
+            .await
+            {
+                Ok(cost) => cost,
+                Err(_) => {
+                    return Err(Errors::new(&[(
+                        "specification",
+                        "failed to value specification stock",
+                    )]))
+                }
+            };
+
+            let price = branch.round_price(cost);
+            let unit_price = branch.round_price(cost / exploded_specification.quantity);
+
+            let specification_history = match SpecificationHistory::create(
+                db_transaction,
+                exploded_specification.specification_id,
+                None,
+                created_by,
+                note.to_owned(),
+                String::from("OUT"),
+                exploded_specification.quantity,
+                price,
+                unit_price,
+                None,
+            )
+            .await
+            {
+                Ok(specification_history) => specification_history,
+                Err(_) => {
+                    return Err(Errors::new(&[(
+                        "specification_history",
+                        "failed to create specification history",
+                    )]))
+                }
+            };
+
+            if ProductionItem::create(
+                db_transaction,
+                production.id,
+                exploded_specification.specification_id,
+                specification_history.id,
+                exploded_specification.quantity,
+                price,
+            )
+            .await
+            .is_err()
+            {
+                return Err(Errors::new(&[(
+                    "production_item",
+                    "failed to create production item",
+                )]));
+            }
+
+            total_cost += price;
+        }
+    }
+
+    let unit_cost = branch.round_price(total_cost / payload.quantity);
+
+    let specification_history = match SpecificationHistory::create(
+        db_transaction,
+        specification.id,
+        None,
+        created_by,
+        note,
+        String::from("IN"),
+        payload.quantity,
+        total_cost,
+        unit_cost,
+        None,
+    )
+    .await
+    {
+        Ok(specification_history) => specification_history,
+        Err(_) => {
+            return Err(Errors::new(&[(
+                "specification_history",
+                "failed to create specification history",
+            )]))
+        }
+    };
+
+    match Production::update_output(
+        db_transaction,
+        production.id,
+        specification_history.id,
+        total_cost,
+        unit_cost,
+    )
+    .await
+    {
+        Ok(production) => Ok(production),
+        Err(_) => Err(Errors::new(&[(
+            "production",
+            "failed to update production output",
+        )])),
+    }
+}
@@ -0,0 +1,403 @@
+use crate::errors::{Errors, FieldValidator};
+use crate::logger::Logger;
+use crate::models::branch::Branch;
+use crate::models::purchase_order::{
+    PurchaseOrder, PurchaseOrderItem, PurchaseOrderReceipt, PurchaseOrderReceiptItem,
+};
+use crate::models::requests::purchase_order::{
+    RequestCreatePurchaseOrder, RequestReceivePurchaseOrder,
+};
+use crate::models::responses::DefaultResponse;
+use crate::models::specification::Specification;
+use crate::models::specification_history::SpecificationHistory;
+use crate::models::supplier::Supplier;
+
+use axum::extract::Path;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use axum::{extract::State, response::Json};
+use reqwest::StatusCode;
+use rust_decimal::Decimal;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn create(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+    Path((branch_id,)): Path<(Uuid,)>,
+    Json(payload): Json<RequestCreatePurchaseOrder>,
+) -> Response {
+    let branch = Branch::get_by_id(&db, branch_id).await;
+
+    if branch.is_err() {
+        let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    if payload.items.is_empty() {
+        let body = DefaultResponse::error("Purchase order has no items", Some("items can't be empty".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    if let Some(supplier_id) = payload.supplier_id {
+        let supplier = Supplier::get_by_id(&db, supplier_id).await;
+
+        if supplier.is_err() || supplier.unwrap().branch_id != branch_id {
+            let body = DefaultResponse::error("Supplier not found", Some("Supplier ID not found".to_string())).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    }
+
+    for item in payload.items.iter() {
+        match FieldValidator::validate(item).check() {
+            Ok(_) => (),
+            Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+        };
+
+        let specification = Specification::get_by_id(&db, item.specification_id).await;
+
+        if specification.is_err() || specification.unwrap().branch_id != branch_id {
+            let body = DefaultResponse::error("Specification not found", Some(format!("Specification {} not found", item.specification_id))).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    }
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    let purchase_order = match PurchaseOrder::create(
+        &mut db_transaction,
+        branch_id,
+        payload.supplier_id,
+        user_id,
+        payload.note,
+    )
+    .await
+    {
+        Ok(purchase_order) => purchase_order,
+        Err(err) => {
+            Logger::new(format!("{:?}", err)).log();
+
+            let body = DefaultResponse::error("Something went wrong", Some("Failed to create purchase order".to_string())).into_json();
+            return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+        }
+    };
+
+    for item in payload.items.iter() {
+        let result = PurchaseOrderItem::create(
+            &mut db_transaction,
+            purchase_order.id,
+            item.specification_id,
+            item.quantity,
+            item.unit_price,
+        )
+        .await;
+
+        if let Err(err) = result {
+            Logger::new(format!("{:?}", err)).log();
+
+            db_transaction
+                .rollback()
+                .await
+                .expect("Failed to rollback transaction");
+
+            let body = DefaultResponse::error("Something went wrong", Some("Failed to create purchase order item, specification may be duplicated".to_string())).into_json();
+            return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+        }
+    }
+
+    let commit = db_transaction.commit().await;
+
+    if commit.is_err() {
+        let body = DefaultResponse::error("Something went wrong", Some("Failed to commit db_transaction".to_string())).into_json();
+        return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+    }
+
+    let purchase_order = PurchaseOrder::get_by_id_with_items(&db, branch_id, purchase_order.id)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::created("Create purchase order successfully")
+        .with_data(json!(purchase_order)).into_json();
+
+    (StatusCode::CREATED, body).into_response()
+}
+
+pub async fn get_by_branch_id(
+    State(db): State<PgPool>,
+    Path((branch_id,)): Path<(Uuid,)>,
+) -> Response {
+    let branch = Branch::get_by_id(&db, branch_id).await;
+
+    if branch.is_err() {
+        let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let purchase_orders = PurchaseOrder::get_by_branch_id(&db, branch_id).await.unwrap();
+
+    let body = DefaultResponse::ok("Get all purchase orders successfully")
+        .with_data(json!(purchase_orders)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn get_by_id(
+    State(db): State<PgPool>,
+    Path((branch_id, purchase_order_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let purchase_order = PurchaseOrder::get_by_id_with_items(&db, branch_id, purchase_order_id).await;
+
+    if purchase_order.is_err() {
+        let body = DefaultResponse::error("Purchase order not found", Some("Purchase order ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let body = DefaultResponse::ok("Get purchase order successfully")
+        .with_data(json!(purchase_order.unwrap())).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn approve(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+    Path((branch_id, purchase_order_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let purchase_order = PurchaseOrder::get_by_id_with_items(&db, branch_id, purchase_order_id).await;
+
+    if purchase_order.is_err() {
+        let body = DefaultResponse::error("Purchase order not found", Some("Purchase order ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    if PurchaseOrder::approve(&db, purchase_order_id, user_id).await.is_err() {
+        let body = DefaultResponse::error("Purchase order cannot be approved", Some("Only DRAFT purchase order can be approved".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let purchase_order = PurchaseOrder::get_by_id_with_items(&db, branch_id, purchase_order_id)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Approve purchase order successfully")
+        .with_data(json!(purchase_order)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn receive(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+    Path((branch_id, purchase_order_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<RequestReceivePurchaseOrder>,
+) -> Response {
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    for item in payload.items.iter() {
+        match FieldValidator::validate(item).check() {
+            Ok(_) => (),
+            Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+        };
+    }
+
+    let branch = match Branch::get_by_id(&db, branch_id).await {
+        Ok(branch) => branch,
+        Err(_) => {
+            let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    };
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    let purchase_order =
+        match PurchaseOrder::get_by_id_for_update(&mut db_transaction, branch_id, purchase_order_id).await {
+            Ok(purchase_order) => purchase_order,
+            Err(_) => {
+                let body = DefaultResponse::error("Purchase order not found", Some("Purchase order ID not found".to_string())).into_json();
+                return (StatusCode::BAD_REQUEST, body).into_response();
+            }
+        };
+
+    if purchase_order.status != "APPROVED" && purchase_order.status != "PARTIALLY_RECEIVED" {
+        let body = DefaultResponse::error("Purchase order cannot be received", Some("Only APPROVED or PARTIALLY_RECEIVED purchase order can be received".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    if let Err(err) = process_receive(&mut db_transaction, &branch, &purchase_order, user_id, &payload).await {
+        Logger::new(format!("{:?}", err)).log();
+
+        db_transaction
+            .rollback()
+            .await
+            .expect("Failed to rollback transaction");
+
+        return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response();
+    }
+
+    let commit = db_transaction.commit().await;
+
+    if commit.is_err() {
+        let body = DefaultResponse::error("Something went wrong", Some("Failed to commit db_transaction".to_string())).into_json();
+        return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+    }
+
+    let purchase_order = PurchaseOrder::get_by_id_with_items(&db, branch_id, purchase_order_id)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Receive purchase order successfully")
+        .with_data(json!(purchase_order)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn close(
+    State(db): State<PgPool>,
+    Path((branch_id, purchase_order_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let purchase_order = PurchaseOrder::get_by_id_with_items(&db, branch_id, purchase_order_id).await;
+
+    if purchase_order.is_err() {
+        let body = DefaultResponse::error("Purchase order not found", Some("Purchase order ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    if PurchaseOrder::close(&db, purchase_order_id).await.is_err() {
+        let body = DefaultResponse::error("Purchase order already closed", Some("Purchase order status is CLOSED".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let purchase_order = PurchaseOrder::get_by_id_with_items(&db, branch_id, purchase_order_id)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Close purchase order successfully")
+        .with_data(json!(purchase_order)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+// post one IN history per delivered line and close the purchase order once nothing is outstanding
+async fn process_receive(
+    db_transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    branch: &Branch,
+    purchase_order: &PurchaseOrder,
+    received_by: Uuid,
+    payload: &RequestReceivePurchaseOrder,
+) -> Result<(), Errors> {
+    let receipt = match PurchaseOrderReceipt::create(
+        db_transaction,
+        purchase_order.id,
+        received_by,
+        payload.note.to_owned(),
+    )
+    .await
+    {
+        Ok(receipt) => receipt,
+        Err(_) => {
+            return Err(Errors::new(&[(
+                "purchase_order_receipt",
+                "failed to create purchase order receipt",
+            )]))
+        }
+    };
+
+    for item in payload.items.iter() {
+        let purchase_order_item = match PurchaseOrderItem::receive(
+            db_transaction,
+            purchase_order.id,
+            item.specification_id,
+            item.quantity,
+        )
+        .await
+        {
+            Ok(purchase_order_item) => purchase_order_item,
+            Err(_) => {
+                return Err(Errors::new(&[(
+                    "items",
+                    "specification is not on the purchase order or exceeds the outstanding quantity",
+                )]))
+            }
+        };
+
+        let price = branch.round_price(item.quantity * purchase_order_item.unit_price);
+
+        let specification_history = match SpecificationHistory::create(
+            db_transaction,
+            item.specification_id,
+            None,
+            received_by,
+            Some(format!("Purchase order {}", purchase_order.id)),
+            String::from("IN"),
+            item.quantity,
+            price,
+            purchase_order_item.unit_price,
+            purchase_order.supplier_id,
+        )
+        .await
+        {
+            Ok(specification_history) => specification_history,
+            Err(_) => {
+                return Err(Errors::new(&[(
+                    "specification_history",
+                    "failed to create specification history",
+                )]))
+            }
+        };
+
+        if PurchaseOrderReceiptItem::create(
+            db_transaction,
+            receipt.id,
+            purchase_order_item.id,
+            specification_history.id,
+            item.quantity,
+        )
+        .await
+        .is_err()
+        {
+            return Err(Errors::new(&[(
+                "purchase_order_receipt_item",
+                "failed to create purchase order receipt item",
+            )]));
+        }
+    }
+
+    let outstanding_quantity =
+        match PurchaseOrderItem::get_outstanding_quantity(db_transaction, purchase_order.id).await {
+            Ok(outstanding_quantity) => outstanding_quantity,
+            Err(_) => {
+                return Err(Errors::new(&[(
+                    "purchase_order",
+                    "failed to compute outstanding quantity",
+                )]))
+            }
+        };
+
+    let status = if outstanding_quantity > Decimal::ZERO {
+        "PARTIALLY_RECEIVED"
+    } else {
+        "CLOSED"
+    };
+
+    if PurchaseOrder::update_status(db_transaction, purchase_order.id, status)
+        .await
+        .is_err()
+    {
+        return Err(Errors::new(&[(
+            "purchase_order",
+            "failed to update purchase order status",
+        )]));
+    }
+
+    Ok(())
+}
@@ -1,11 +1,15 @@
+use std::sync::Arc;
+
 use crate::errors::FieldValidator;
+use crate::handlers::email_verification;
+use crate::mailer::Mailer;
 use crate::models::responses::DefaultResponse;
 use crate::models::{requests::user::RequestCreateUser, user::User};
 
-use argon2::{self, Config};
-use argon2::{ThreadMode, Variant, Version};
+use crate::password;
 
 use axum::response::{Response, IntoResponse};
+use axum::Extension;
 use axum::{extract::State, response::Json};
 use reqwest::StatusCode;
 use serde_json::json;
@@ -13,6 +17,7 @@ use sqlx::PgPool;
 
 pub async fn register(
     State(db): State<PgPool>,
+    Extension(mailer): Extension<Arc<dyn Mailer>>,
     Json(payload): Json<RequestCreateUser>,
 ) -> Response {
     let mut extractor = FieldValidator::validate(&payload);
@@ -29,28 +34,13 @@ pub async fn register(
         return (StatusCode::BAD_REQUEST, body).into_response();
     }
 
-    let salt = std::env::var("APPKEY").unwrap();
-    let config = Config {
-        variant: Variant::Argon2i,
-        version: Version::Version13,
-        mem_cost: 512,
-        time_cost: 2,
-        lanes: 1,
-        thread_mode: ThreadMode::Parallel,
-        secret: &[],
-        ad: &[],
-        hash_length: 32,
-    };
-
     let email = email.trim().to_string().to_lowercase();
-    let password = password.trim().to_string().to_lowercase();
-    let hash = argon2::hash_encoded(password.as_bytes(), salt.as_bytes(), &config).unwrap();
-
-    let matches = argon2::verify_encoded(&hash, password.as_bytes()).unwrap();
-    assert!(matches);
+    let hash = password::hash(&password).await;
 
     let user = User::create(&db, name, email, hash).await.unwrap();
 
+    email_verification::send_verification(&db, mailer, &user).await.unwrap();
+
     let body =
         DefaultResponse::created("Register successfully").with_data(json!(user)).into_json();
 
@@ -0,0 +1,67 @@
+use crate::models::oauth_access_token::OauthAccessToken;
+use crate::models::responses::DefaultResponse;
+
+use axum::extract::Path;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use axum::extract::State;
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn logout(
+    State(db): State<PgPool>,
+    Extension(oauth_access_token): Extension<OauthAccessToken>,
+) -> Response {
+    OauthAccessToken::delete(&db, oauth_access_token.access_token)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Logout successfully").into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn get_all(
+    State(db): State<PgPool>,
+    Extension(oauth_access_token): Extension<OauthAccessToken>,
+) -> Response {
+    let sessions = OauthAccessToken::get_sessions_by_user_id(&db, oauth_access_token.user_id)
+        .await
+        .unwrap();
+
+    let sessions = sessions
+        .into_iter()
+        .map(|session| {
+            let is_current = session.id == oauth_access_token.id;
+            json!({
+                "id": session.id,
+                "user_agent": session.user_agent,
+                "created_at": session.created_at,
+                "expires_at": session.expires_at,
+                "is_current": is_current,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let body = DefaultResponse::ok("Get all sessions successfully")
+        .with_data(json!(sessions)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn delete(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+    Path((session_id,)): Path<(Uuid,)>,
+) -> Response {
+    if OauthAccessToken::revoke_by_id(&db, session_id, user_id).await.is_err() {
+        let body = DefaultResponse::error("Session not found", Some("Session ID not found or already revoked".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let body = DefaultResponse::ok("Revoke session successfully").into_json();
+
+    (StatusCode::OK, body).into_response()
+}
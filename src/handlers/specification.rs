@@ -1,15 +1,18 @@
 use crate::errors::FieldValidator;
 use crate::models::branch::Branch;
-use crate::models::requests::specification::RequestFormSpecification;
+use crate::models::requests::specification::{
+    RequestFormReorderPoint, RequestFormSpecification, RequestQueryBalance,
+};
 use crate::models::responses::DefaultResponse;
 use crate::models::specification::Specification;
+use crate::models::specification_history::SpecificationHistory;
+use crate::models::unit::Unit;
 
-use axum::extract::Path;
+use axum::extract::{Path, Query};
 use axum::response::{Response, IntoResponse};
 use axum::{extract::State, response::Json};
 use reqwest::StatusCode;
 use rust_decimal::Decimal;
-use rust_decimal::prelude::ToPrimitive;
 use serde_json::json;
 use sqlx::PgPool;
 use uuid::Uuid;
@@ -35,10 +38,26 @@ pub async fn create(
     let raw_price = extractor.extract("raw_price", Some(payload.raw_price));
     extractor.check();
 
-    let lowest_price = match (Decimal::from(raw_price) / Decimal::from(smallest_unit)) 
-        .round_dp(2)
-        .to_f64() {
-            Some(val) => val,
+    if let Some(unit_id) = payload.unit_id {
+        let unit = Unit::get_by_id(&db, unit_id).await;
+
+        if unit.is_err() || unit.unwrap().branch_id.is_some_and(|id| id != branch_id) {
+            let body = DefaultResponse::error("Unit not found", Some("Unit ID not found".to_string())).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    }
+
+    // fall back to the catalog unit matching unit_name, the same way import does
+    let unit_id = match payload.unit_id {
+        Some(unit_id) => Some(unit_id),
+        None => Unit::get_by_code_or_name(&db, &branch_id, &unit_name)
+            .await
+            .ok()
+            .map(|unit| unit.id),
+    };
+
+    let lowest_price = match raw_price.checked_div(Decimal::from(smallest_unit)) {
+            Some(val) => branch.as_ref().unwrap().round_price(val),
             None => {
                 let body = DefaultResponse::error("Failed to generate lowest price", Some("Unabel to convert raw price / smallest unit into lowest price".to_string())).into_json();
                 return (StatusCode::BAD_REQUEST, body).into_response();
@@ -54,6 +73,9 @@ pub async fn create(
         unit,
         lowest_price,
         raw_price,
+        payload.min_stock,
+        payload.reorder_quantity,
+        unit_id,
     )
     .await
     .unwrap();
@@ -85,6 +107,38 @@ pub async fn get_by_branch_id(
     (StatusCode::OK, body).into_response()
 }
 
+pub async fn update_reorder_point(
+    State(db): State<PgPool>,
+    Path((branch_id, specification_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<RequestFormReorderPoint>,
+) -> Response {
+    let specification = Specification::get_by_id(&db, specification_id).await;
+
+    if specification.is_err() || specification.as_ref().unwrap().branch_id != branch_id {
+        let body = DefaultResponse::error("Specification not found", Some("Specification ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let specification = Specification::update_reorder_point(
+        &db,
+        specification_id,
+        payload.min_stock,
+        payload.reorder_quantity,
+    )
+    .await
+    .unwrap();
+
+    let body = DefaultResponse::ok("Update specification reorder point successfully")
+        .with_data(json!(specification)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
 pub async fn delete(
     State(db): State<PgPool>,
     Path((branch_id, specification_id)): Path<(Uuid, Uuid)>,
@@ -96,10 +150,17 @@ pub async fn delete(
         return (StatusCode::BAD_REQUEST, body).into_response();
     }
 
+    let specification = Specification::get_by_id(&db, specification_id).await;
+
+    if specification.is_err() || specification.unwrap().branch_id != branch_id {
+        let body = DefaultResponse::error("Specification not found", Some("Specification ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
     let result = Specification::delete(&db, specification_id).await;
 
     if result.is_err() {
-        let body = DefaultResponse::error("Specification not found", Some(result.err().unwrap().to_string())).into_json();
+        let body = DefaultResponse::error("Specification not found", Some("Specification ID not found".to_string())).into_json();
         return (StatusCode::BAD_REQUEST, body).into_response();
     }
 
@@ -107,3 +168,28 @@ pub async fn delete(
 
     (StatusCode::OK, body).into_response()
 }
+
+
+pub async fn balance(
+    State(db): State<PgPool>,
+    Path((branch_id, specification_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<RequestQueryBalance>,
+) -> Response {
+    let specification = Specification::get_by_id(&db, specification_id).await;
+
+    if specification.is_err() || specification.as_ref().unwrap().branch_id != branch_id {
+        let body = DefaultResponse::error("Specification not found", Some("Specification ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let at = query.at.unwrap_or_else(chrono::Utc::now).naive_utc();
+
+    let balance = SpecificationHistory::get_balance(&db, specification_id, at)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Get specification balance successfully")
+        .with_data(json!(balance)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
@@ -1,9 +1,11 @@
-use crate::errors::FieldValidator;
+use crate::errors::{Errors, FieldValidator};
 use crate::models::branch::Branch;
 use crate::models::requests::specification::RequestFormSpecificationHistory;
 use crate::models::responses::DefaultResponse;
 use crate::models::specification::Specification;
 use crate::models::specification_history::SpecificationHistory;
+use crate::models::supplier::Supplier;
+use crate::models::unit::Unit;
 use crate::models::user::User;
 
 use axum::extract::Path;
@@ -28,11 +30,20 @@ pub async fn create(
 
     let specification = Specification::get_by_id(&db, specification_id).await;
 
-    if specification.is_err() {
+    if specification.is_err() || specification.as_ref().unwrap().branch_id != branch_id {
         let body = DefaultResponse::error("Specification not found", Some("Specification ID not found".to_string())).into_json();
         return (StatusCode::BAD_REQUEST, body).into_response();
     }
 
+    if let Some(supplier_id) = payload.supplier_id {
+        let supplier = Supplier::get_by_id(&db, supplier_id).await;
+
+        if supplier.is_err() || supplier.unwrap().branch_id != branch_id {
+            let body = DefaultResponse::error("Supplier not found", Some("Supplier ID not found".to_string())).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    }
+
     let user = User::get_by_id(&db, payload.created_by).await;
 
     if user.is_err() {
@@ -45,7 +56,24 @@ pub async fn create(
     let quantity = extractor.extract("quantity", Some(payload.quantity));
     let flow_type = extractor.extract("flow_type", Some(payload.flow_type));
     let price = extractor.extract("price", Some(payload.price));
-    extractor.check();
+    match extractor.check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let quantity = match &payload.unit {
+        Some(unit) => match Unit::convert_to_specification_unit(&db, specification.as_ref().unwrap(), unit, quantity).await {
+            Ok(quantity) => quantity,
+            Err(code) => return (StatusCode::UNPROCESSABLE_ENTITY, Errors::new(&[("unit", code)]).into_response()).into_response(),
+        },
+        None => quantity,
+    };
+
+    // a unit conversion can still round a tiny quantity down to zero
+    let unit_price = match price.checked_div(quantity) {
+        Some(unit_price) => branch.as_ref().unwrap().round_price(unit_price),
+        None => return (StatusCode::UNPROCESSABLE_ENTITY, Errors::new(&[("quantity", "quantity must be greater than 0")]).into_response()).into_response(),
+    };
 
     let mut db_transaction = db.begin().await.unwrap();
 
@@ -58,7 +86,8 @@ pub async fn create(
         flow_type,
         quantity,
         price,
-        price / quantity as f64,
+        unit_price,
+        payload.supplier_id,
     )
     .await
     .unwrap();
@@ -75,3 +104,14 @@ pub async fn create(
 
     (StatusCode::CREATED, body).into_response()
 }
+
+// waste always leaves the stock, staff record it here since purchase also takes priced IN entries
+pub async fn waste(
+    state: State<PgPool>,
+    path: Path<(Uuid, Uuid)>,
+    Json(mut payload): Json<RequestFormSpecificationHistory>,
+) -> Response {
+    payload.flow_type = String::from("OUT");
+
+    create(state, path, Json(payload)).await
+}
@@ -0,0 +1,161 @@
+use crate::errors::{Errors, FieldValidator};
+use crate::logger::Logger;
+use crate::models::requests::specification::RequestFormSpecificationRecipe;
+use crate::models::responses::DefaultResponse;
+use crate::models::specification::Specification;
+use crate::models::specification_recipe::SpecificationRecipe;
+
+use axum::extract::Path;
+use axum::response::{IntoResponse, Response};
+use axum::{extract::State, response::Json};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn update(
+    State(db): State<PgPool>,
+    Path((branch_id, specification_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<RequestFormSpecificationRecipe>,
+) -> Response {
+    let specification = Specification::get_by_id(&db, specification_id).await;
+
+    if specification.is_err() || specification.unwrap().branch_id != branch_id {
+        let body = DefaultResponse::error("Specification not found", Some("Specification ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    for component in payload.components.iter() {
+        match FieldValidator::validate(component).check() {
+            Ok(_) => (),
+            Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+        };
+
+        let component_specification = Specification::get_by_id(&db, component.specification_id).await;
+
+        if component_specification.is_err() || component_specification.unwrap().branch_id != branch_id {
+            let body = DefaultResponse::error("Specification not found", Some(format!("Component specification {} not found", component.specification_id))).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    }
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    if let Err(err) = process_update(&mut db_transaction, specification_id, &payload).await {
+        Logger::new(format!("{:?}", err)).log();
+
+        db_transaction
+            .rollback()
+            .await
+            .expect("Failed to rollback transaction");
+
+        return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response();
+    }
+
+    let commit = db_transaction.commit().await;
+
+    if commit.is_err() {
+        let body = DefaultResponse::error("Something went wrong", Some("Failed to commit db_transaction".to_string())).into_json();
+        return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+    }
+
+    let specification = Specification::get_by_id(&db, specification_id).await.unwrap();
+    let components = SpecificationRecipe::get_components_by_specification_id(&db, specification_id)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Update specification recipe successfully")
+        .with_data(json!({ "specification": specification, "components": components })).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn get_by_specification_id(
+    State(db): State<PgPool>,
+    Path((branch_id, specification_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let specification = Specification::get_by_id(&db, specification_id).await;
+
+    if specification.is_err() || specification.as_ref().unwrap().branch_id != branch_id {
+        let body = DefaultResponse::error("Specification not found", Some("Specification ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let components = SpecificationRecipe::get_components_by_specification_id(&db, specification_id)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Get specification recipe successfully")
+        .with_data(json!({ "specification": specification.unwrap(), "components": components })).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+// replace the whole recipe, an empty component list turns the specification back into a raw one
+async fn process_update(
+    db_transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    specification_id: Uuid,
+    payload: &RequestFormSpecificationRecipe,
+) -> Result<(), Errors> {
+    if SpecificationRecipe::delete_by_specification_id(db_transaction, specification_id)
+        .await
+        .is_err()
+    {
+        return Err(Errors::new(&[(
+            "specification_recipe",
+            "failed to delete specification recipe",
+        )]));
+    }
+
+    for component in payload.components.iter() {
+        if SpecificationRecipe::create(
+            db_transaction,
+            specification_id,
+            component.specification_id,
+            component.quantity,
+        )
+        .await
+        .is_err()
+        {
+            return Err(Errors::new(&[(
+                "components",
+                "component is duplicated or is the specification itself",
+            )]));
+        }
+    }
+
+    let recipe_yield = if payload.components.is_empty() {
+        None
+    } else {
+        Some(payload.recipe_yield)
+    };
+
+    let is_prepared = !payload.components.is_empty() && payload.is_prepared.unwrap_or(false);
+
+    if Specification::update_recipe(db_transaction, specification_id, recipe_yield, is_prepared)
+        .await
+        .is_err()
+    {
+        return Err(Errors::new(&[(
+            "recipe_yield",
+            "failed to update recipe yield",
+        )]));
+    }
+
+    match SpecificationRecipe::has_cycle(db_transaction, specification_id).await {
+        Ok(false) => Ok(()),
+        Ok(true) => Err(Errors::new(&[(
+            "components",
+            "recipe cannot contain the specification itself at any level",
+        )])),
+        Err(_) => Err(Errors::new(&[(
+            "components",
+            "failed to check recipe tree",
+        )])),
+    }
+}
@@ -0,0 +1,62 @@
+use crate::logger::Logger;
+use crate::models::branch::Branch;
+use crate::models::responses::DefaultResponse;
+use crate::models::stock_balance::StockBalance;
+
+use axum::extract::{Path, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+// recompute the stock ledger of a branch from specification histories and report every drift found
+pub async fn rebuild(
+    State(db): State<PgPool>,
+    Path((branch_id,)): Path<(Uuid,)>,
+) -> Response {
+    let branch = Branch::get_by_id(&db, branch_id).await;
+
+    if branch.is_err() {
+        let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    let drifts = match StockBalance::get_drifts_by_branch_id(&mut db_transaction, branch_id).await {
+        Ok(drifts) => drifts,
+        Err(err) => {
+            Logger::new(format!("{:?}", err)).log();
+
+            let body = DefaultResponse::error("Something went wrong", Some("Failed to compute stock balance drifts".to_string())).into_json();
+            return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+        }
+    };
+
+    let stock_balances = match StockBalance::rebuild_by_branch_id(&mut db_transaction, branch_id).await {
+        Ok(stock_balances) => stock_balances,
+        Err(err) => {
+            Logger::new(format!("{:?}", err)).log();
+
+            let body = DefaultResponse::error("Something went wrong", Some("Failed to rebuild stock balances".to_string())).into_json();
+            return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+        }
+    };
+
+    let commit = db_transaction.commit().await;
+
+    if commit.is_err() {
+        let body = DefaultResponse::error("Something went wrong", Some("Failed to commit db_transaction".to_string())).into_json();
+        return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+    }
+
+    let body = DefaultResponse::ok("Rebuild stock balances successfully")
+        .with_data(json!({
+            "rebuilt": stock_balances.len(),
+            "drifts": drifts,
+        }))
+        .into_json();
+
+    (StatusCode::OK, body).into_response()
+}
@@ -0,0 +1,178 @@
+use crate::errors::FieldValidator;
+use crate::models::branch::Branch;
+use crate::models::requests::supplier::{RequestFormSpecificationSupplier, RequestFormSupplier};
+use crate::models::responses::DefaultResponse;
+use crate::models::specification::Specification;
+use crate::models::supplier::{SpecificationSupplier, Supplier};
+
+use axum::extract::Path;
+use axum::response::{IntoResponse, Response};
+use axum::{extract::State, response::Json};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn create(
+    State(db): State<PgPool>,
+    Path((branch_id,)): Path<(Uuid,)>,
+    Json(payload): Json<RequestFormSupplier>,
+) -> Response {
+    let branch = Branch::get_by_id(&db, branch_id).await;
+
+    if branch.is_err() {
+        let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let supplier = Supplier::create(
+        &db,
+        branch_id,
+        payload.name,
+        payload.contact,
+        payload.lead_time_days,
+    )
+    .await
+    .unwrap();
+
+    let body = DefaultResponse::created("Create supplier successfully")
+        .with_data(json!(supplier)).into_json();
+
+    (StatusCode::CREATED, body).into_response()
+}
+
+pub async fn get_by_branch_id(
+    State(db): State<PgPool>,
+    Path((branch_id,)): Path<(Uuid,)>,
+) -> Response {
+    let branch = Branch::get_by_id(&db, branch_id).await;
+
+    if branch.is_err() {
+        let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let suppliers = Supplier::get_by_branch_id(&db, branch_id).await.unwrap();
+
+    let body = DefaultResponse::ok("Get all suppliers successfully")
+        .with_data(json!(suppliers)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn update(
+    State(db): State<PgPool>,
+    Path((branch_id, supplier_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<RequestFormSupplier>,
+) -> Response {
+    let supplier = Supplier::get_by_id(&db, supplier_id).await;
+
+    if supplier.is_err() || supplier.unwrap().branch_id != branch_id {
+        let body = DefaultResponse::error("Supplier not found", Some("Supplier ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let supplier = Supplier::update(
+        &db,
+        supplier_id,
+        payload.name,
+        payload.contact,
+        payload.lead_time_days,
+    )
+    .await
+    .unwrap();
+
+    let body = DefaultResponse::ok("Update supplier successfully")
+        .with_data(json!(supplier)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn delete(
+    State(db): State<PgPool>,
+    Path((branch_id, supplier_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let supplier = Supplier::get_by_id(&db, supplier_id).await;
+
+    if supplier.is_err() || supplier.unwrap().branch_id != branch_id {
+        let body = DefaultResponse::error("Supplier not found", Some("Supplier ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    Supplier::delete(&db, supplier_id).await.unwrap();
+
+    let body = DefaultResponse::ok("Delete supplier successfully").into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn set_specification_supplier(
+    State(db): State<PgPool>,
+    Path((branch_id, specification_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<RequestFormSpecificationSupplier>,
+) -> Response {
+    let specification = Specification::get_by_id(&db, specification_id).await;
+
+    if specification.is_err() || specification.unwrap().branch_id != branch_id {
+        let body = DefaultResponse::error("Specification not found", Some("Specification ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let supplier = Supplier::get_by_id(&db, payload.supplier_id).await;
+
+    if supplier.is_err() || supplier.unwrap().branch_id != branch_id {
+        let body = DefaultResponse::error("Supplier not found", Some("Supplier ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let specification_supplier = SpecificationSupplier::upsert(
+        &db,
+        specification_id,
+        payload.supplier_id,
+        payload.pack_size,
+        payload.price,
+    )
+    .await
+    .unwrap();
+
+    let body = DefaultResponse::ok("Set specification supplier successfully")
+        .with_data(json!(specification_supplier)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn get_by_specification_id(
+    State(db): State<PgPool>,
+    Path((branch_id, specification_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let specification = Specification::get_by_id(&db, specification_id).await;
+
+    if specification.is_err() || specification.unwrap().branch_id != branch_id {
+        let body = DefaultResponse::error("Specification not found", Some("Specification ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let comparisons = SpecificationSupplier::get_comparison_by_specification_id(&db, specification_id)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Get specification suppliers successfully")
+        .with_data(json!(comparisons)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
@@ -0,0 +1,107 @@
+use crate::config::Config;
+use crate::errors::FieldValidator;
+use crate::logger::Logger;
+use crate::models::oauth_access_token::OauthAccessToken;
+use crate::models::oauth_refresh_token::OauthRefreshToken;
+use crate::models::requests::login::RequestRefreshToken;
+use crate::models::responses::DefaultResponse;
+use crate::token;
+
+use axum::response::{IntoResponse, Response};
+use axum::{extract::State, response::Json};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+
+pub async fn refresh(
+    State(db): State<PgPool>,
+    Json(payload): Json<RequestRefreshToken>,
+) -> Response {
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let config = Config::from_env().unwrap();
+    let now = chrono::Utc::now().naive_utc();
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    let oauth_refresh_token = match OauthRefreshToken::get_by_refresh_token_for_update(
+        &mut db_transaction,
+        token::digest(&payload.refresh_token),
+    )
+    .await
+    {
+        Ok(oauth_refresh_token) => oauth_refresh_token,
+        Err(_) => {
+            db_transaction.rollback().await.unwrap();
+
+            let body = DefaultResponse::unauthorized("Refresh token invalid", Some("refresh_token is not exist".to_string())).into_json();
+            return (StatusCode::UNAUTHORIZED, body).into_response();
+        }
+    };
+
+    // a rotated token showing up again means it leaked, sign the whole session out
+    if oauth_refresh_token.used_at.is_some() {
+        OauthAccessToken::revoke_session(&mut db_transaction, oauth_refresh_token.oauth_access_token_id)
+            .await
+            .unwrap();
+
+        db_transaction.commit().await.unwrap();
+
+        Logger::new(format!("refresh token reused, session {} revoked", oauth_refresh_token.oauth_access_token_id)).log();
+
+        let body = DefaultResponse::unauthorized("Refresh token invalid", Some("refresh_token was already used, session revoked".to_string())).into_json();
+        return (StatusCode::UNAUTHORIZED, body).into_response();
+    }
+
+    if oauth_refresh_token.expires_at <= now {
+        db_transaction.rollback().await.unwrap();
+
+        let body = DefaultResponse::unauthorized("Refresh token invalid", Some("refresh_token is expired".to_string())).into_json();
+        return (StatusCode::UNAUTHORIZED, body).into_response();
+    }
+
+    let access_token = token::generate();
+
+    let oauth_access_token = match OauthAccessToken::rotate(
+        &mut db_transaction,
+        oauth_refresh_token.oauth_access_token_id,
+        token::digest(&access_token),
+        now + config.access_token_ttl(),
+    )
+    .await
+    {
+        Ok(oauth_access_token) => oauth_access_token,
+        Err(_) => {
+            db_transaction.rollback().await.unwrap();
+
+            let body = DefaultResponse::unauthorized("Refresh token invalid", Some("session is revoked or expired".to_string())).into_json();
+            return (StatusCode::UNAUTHORIZED, body).into_response();
+        }
+    };
+
+    OauthRefreshToken::mark_used(&mut db_transaction, oauth_refresh_token.id)
+        .await
+        .unwrap();
+
+    let refresh_token = token::generate();
+
+    OauthRefreshToken::create(
+        &mut db_transaction,
+        oauth_access_token.id,
+        token::digest(&refresh_token),
+        oauth_access_token.session_expires_at,
+    )
+    .await
+    .unwrap();
+
+    db_transaction.commit().await.unwrap();
+
+    let body = DefaultResponse::ok("Refresh token successfully")
+        .with_access_token(access_token)
+        .with_refresh_token(refresh_token)
+        .into_json();
+
+    (StatusCode::OK, body).into_response()
+}
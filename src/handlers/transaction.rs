@@ -1,19 +1,25 @@
-use crate::errors::Errors;
+use crate::errors::{Errors, FieldValidator};
 use crate::logger::Logger;
 use crate::models::branch::Branch;
 use crate::models::product::Product;
 use crate::models::requests::transaction::{
-    RequestCreateTransaction, RequestCreateTransactionItem,
+    RequestCreateTransaction, RequestQueryTransaction, RequestRefundTransaction,
+    RequestUpdateTransactionStatus, RequestVoidTransaction,
 };
 use crate::models::responses::DefaultResponse;
 use crate::models::specification_history::SpecificationHistory;
-use crate::models::transaction::{Transaction, TransactionItem};
+use crate::models::specification_recipe::SpecificationRecipe;
+use crate::models::stock_balance::StockBalance;
+use crate::models::stock_reservation::StockReservation;
+use crate::models::transaction::{
+    SimplifyTransaction, Transaction, TransactionItem, TransactionMovement,
+};
 use crate::models::user::User;
 
 use axum::response::{IntoResponse, Response};
-use axum::{extract::Path, extract::State, response::Json};
+use axum::Extension;
+use axum::{extract::Path, extract::Query, extract::State, response::Json};
 use reqwest::StatusCode;
-use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::Decimal;
 use serde_json::json;
 use sqlx::PgPool;
@@ -47,18 +53,21 @@ pub async fn create(
     let transaction_id = match process_create(
         &db,
         &mut db_transaction,
-        &branch_id,
-        payload.created_by,
-        &payload.note,
-        &payload.items,
+        branch.as_ref().unwrap(),
+        &payload,
     )
     .await
     {
         Ok(transaction) => transaction,
         Err(err) => {
-            let body = DefaultResponse::error("Something went wrong", Some("Something went wrong".to_string()))
-                .into_json();
-            return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+            Logger::new(format!("{:?}", err)).log();
+
+            db_transaction
+                .rollback()
+                .await
+                .expect("Failed to rollback transaction");
+
+            return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response();
         }
     };
 
@@ -111,10 +120,8 @@ pub async fn bulk_create(
         let transaction_id = match process_create(
             &db,
             &mut db_transaction,
-            &branch_id,
-            transaction.created_by,
-            &transaction.note,
-            &transaction.items,
+            branch.as_ref().unwrap(),
+            transaction,
         )
         .await
         {
@@ -153,30 +160,290 @@ pub async fn bulk_create(
     }
 }
 
+pub async fn get_by_branch_id(
+    State(db): State<PgPool>,
+    Path((branch_id,)): Path<(Uuid,)>,
+    Query(query): Query<RequestQueryTransaction>,
+) -> Response {
+    let branch = Branch::get_by_id(&db, branch_id).await;
+
+    if branch.is_err() {
+        let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    match FieldValidator::validate(&query).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let page = query.page.unwrap_or(1);
+    let per_page = query.per_page.unwrap_or(20);
+
+    let transactions = SimplifyTransaction::get_by_branch_id(&db, branch_id, &query, per_page, (page - 1) * per_page)
+        .await
+        .unwrap();
+
+    let total = SimplifyTransaction::count_by_branch_id(&db, branch_id, &query)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Get all transactions successfully")
+        .with_data(json!({
+            "transactions": transactions,
+            "page": page,
+            "per_page": per_page,
+            "total": total,
+        }))
+        .into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn get_by_id(
+    State(db): State<PgPool>,
+    Path((branch_id, transaction_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let transaction = SimplifyTransaction::get_by_id_with_items(&db, branch_id, transaction_id).await;
+
+    if transaction.is_err() {
+        let body = DefaultResponse::error("Transaction not found", Some("Transaction ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let movements = TransactionMovement::get_by_transaction_id(&db, transaction_id)
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Get transaction successfully")
+        .with_data(json!({ "transaction": transaction.unwrap(), "movements": movements })).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn void(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+    Path((branch_id, transaction_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<RequestVoidTransaction>,
+) -> Response {
+    let branch = match Branch::get_by_id(&db, branch_id).await {
+        Ok(branch) => branch,
+        Err(_) => {
+            let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    };
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    let (transaction, items) = match process_void(&mut db_transaction, &branch, transaction_id, user_id, &payload).await {
+        Ok(result) => result,
+        Err(err) => {
+            Logger::new(format!("{:?}", err)).log();
+
+            db_transaction
+                .rollback()
+                .await
+                .expect("Failed to rollback transaction");
+
+            return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response();
+        }
+    };
+
+    let commit = db_transaction.commit().await;
+
+    if commit.is_err() {
+        let body = DefaultResponse::error("Something went wrong", Some("Failed to commit db_transaction".to_string())).into_json();
+        return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+    }
+
+    let body = DefaultResponse::ok("Void transaction successfully")
+        .with_data(json!({ "transaction": transaction, "items": items })).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn refund(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+    Path((branch_id, transaction_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<RequestRefundTransaction>,
+) -> Response {
+    let branch = match Branch::get_by_id(&db, branch_id).await {
+        Ok(branch) => branch,
+        Err(_) => {
+            let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    };
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    if payload.items.is_empty() {
+        let body = DefaultResponse::error("Refund has no items", Some("items can't be empty".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    for item in payload.items.iter() {
+        match FieldValidator::validate(item).check() {
+            Ok(_) => (),
+            Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+        };
+    }
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    let (transaction, items) = match process_refund(&mut db_transaction, &branch, transaction_id, user_id, &payload).await {
+        Ok(result) => result,
+        Err(err) => {
+            Logger::new(format!("{:?}", err)).log();
+
+            db_transaction
+                .rollback()
+                .await
+                .expect("Failed to rollback transaction");
+
+            return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response();
+        }
+    };
+
+    let commit = db_transaction.commit().await;
+
+    if commit.is_err() {
+        let body = DefaultResponse::error("Something went wrong", Some("Failed to commit db_transaction".to_string())).into_json();
+        return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+    }
+
+    let body = DefaultResponse::ok("Refund transaction successfully")
+        .with_data(json!({ "transaction": transaction, "items": items })).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn update_status(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+    Path((branch_id, transaction_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<RequestUpdateTransactionStatus>,
+) -> Response {
+    let branch = match Branch::get_by_id(&db, branch_id).await {
+        Ok(branch) => branch,
+        Err(_) => {
+            let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    };
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    let transaction = match process_update_status(&mut db_transaction, &branch, transaction_id, user_id, &payload).await {
+        Ok(transaction) => transaction,
+        Err(err) => {
+            Logger::new(format!("{:?}", err)).log();
+
+            db_transaction
+                .rollback()
+                .await
+                .expect("Failed to rollback transaction");
+
+            return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response();
+        }
+    };
+
+    let commit = db_transaction.commit().await;
+
+    if commit.is_err() {
+        let body = DefaultResponse::error("Something went wrong", Some("Failed to commit db_transaction".to_string())).into_json();
+        return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+    }
+
+    let body = DefaultResponse::ok("Update transaction status successfully")
+        .with_data(json!(transaction)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
 pub async fn process_create(
     db: &PgPool,
     db_transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
-    branch_id: &Uuid,
-    created_by: Option<Uuid>,
-    note: &Option<String>,
-    items: &Vec<RequestCreateTransactionItem>,
+    branch: &Branch,
+    payload: &RequestCreateTransaction,
 ) -> Result<String, Errors> {
-    let transaction =
-        match Transaction::create(db_transaction, branch_id, created_by, note.to_owned()).await {
-            Ok(transaction) => transaction,
-            Err(err) => {
-                Logger::new(format!("{:?}", err)).log();
+    FieldValidator::validate(payload).check()?;
+
+    let reference_id = payload.transaction_id;
+    let order_status = payload.order_status.to_owned().unwrap_or(String::from("COMPLETED"));
+    let payment_status = match &payload.payment_status {
+        Some(payment_status) => payment_status.to_owned(),
+        None if order_status == "COMPLETED" => String::from("PAID"),
+        None => String::from("UNPAID"),
+    };
 
-                return Err(Errors::new(&[(
-                    "transaction",
-                    "failed to create transaction",
-                )]));
-            }
-        };
+    if order_status == "COMPLETED" && payment_status != "PAID" {
+        return Err(Errors::new(&[(
+            "payment_status",
+            "transaction must be paid before it is completed",
+        )]));
+    }
+
+    // pending orders only hold the stock when the branch deducts on completion
+    let is_reserved = order_status == "PENDING" && branch.stock_deduction == "ON_COMPLETE";
+
+    let transaction = match Transaction::create(
+        db_transaction,
+        &branch.id,
+        reference_id,
+        payload.created_by,
+        payload.note.to_owned(),
+        order_status,
+        payment_status,
+    )
+    .await
+    {
+        Ok(Some(transaction)) => transaction,
+        // a retry of a transaction we already have, hand back the original without touching stock again
+        Ok(None) => {
+            return match Transaction::get_by_reference_id(db_transaction, branch.id, reference_id.unwrap()).await {
+                Ok(transaction) => Ok(transaction.id.to_string()),
+                Err(err) => {
+                    Logger::new(format!("{:?}", err)).log();
+
+                    Err(Errors::new(&[(
+                        "transaction_id",
+                        "failed to get existing transaction",
+                    )]))
+                }
+            };
+        }
+        Err(err) => {
+            Logger::new(format!("{:?}", err)).log();
+
+            return Err(Errors::new(&[(
+                "transaction",
+                "failed to create transaction",
+            )]));
+        }
+    };
+
+    for item in payload.items.iter() {
+        FieldValidator::validate(item).check()?;
 
-    for item in items {
         let result =
-            Product::get_by_reference_id_with_specification(&db, item.product_reference_id).await;
+            Product::get_by_reference_id_with_specification(db, branch.id, item.product_reference_id).await;
 
         if result.is_err() {
             return Err(Errors::new(&[(
@@ -207,41 +474,475 @@ pub async fn process_create(
         let transaction_item = result_transaction_item.unwrap();
 
         for specification in product.specifications.unwrap() {
-            let product_spec_price = specification
-                .product_specification_price
-                .expect("product_spec_price not found");
             let product_spec_quantity = specification
                 .product_specification_quantity
                 .expect("product_spec_quantity not found");
-            let spec_unit_price = specification.unit_price.expect("unit_price not found");
             let transaction_item_spec_quantity =
                 product_spec_quantity * transaction_item.product_quantity;
 
-            let decimal_price = Decimal::from(transaction_item_spec_quantity);
-            let decimal_product_spec_price = Decimal::from_f64(product_spec_price)
-                .expect("failed to convert product_spec_price to decimal");
-
-            let decimal_result = decimal_price * decimal_product_spec_price;
-            let price = decimal_result
-                .round_dp(2)
-                .to_f64()
-                .expect("failed to convert decimal to f64");
-
-            SpecificationHistory::create(
+            // sub-recipes are deducted as the raw specifications they are made of
+            let exploded_specifications = match SpecificationRecipe::explode(
                 db_transaction,
                 specification.id.unwrap(),
-                Some(transaction_item.id),
-                Uuid::parse_str("9f175978-100f-431e-97ad-d4f1ab54ba76").unwrap(),
-                None,
-                String::from("OUT"),
                 transaction_item_spec_quantity,
-                price,
-                spec_unit_price,
             )
             .await
-            .unwrap();
+            {
+                Ok(exploded_specifications) => exploded_specifications,
+                Err(err) => {
+                    Logger::new(format!("{:?}", err)).log();
+
+                    return Err(Errors::new(&[(
+                        "specification",
+                        "failed to explode specification recipe",
+                    )]));
+                }
+            };
+
+            for exploded_specification in exploded_specifications {
+                if !is_reserved {
+                    process_deduct(
+                        db_transaction,
+                        branch,
+                        transaction_item.id,
+                        exploded_specification.specification_id,
+                        exploded_specification.quantity,
+                    )
+                    .await?;
+
+                    continue;
+                }
+
+                // the balance row lock keeps two pending orders from reserving the same stock
+                let on_hand = match StockBalance::get_by_specification_id_for_update(
+                    db_transaction,
+                    exploded_specification.specification_id,
+                )
+                .await
+                {
+                    Ok(stock_balance) => stock_balance.map_or(Decimal::ZERO, |stock_balance| stock_balance.quantity),
+                    Err(err) => {
+                        Logger::new(format!("{:?}", err)).log();
+
+                        return Err(Errors::new(&[(
+                            "stock_reservation",
+                            "failed to reserve specification stock",
+                        )]));
+                    }
+                };
+
+                let reserved = match StockReservation::get_reserved_quantity_by_specification_id(
+                    db_transaction,
+                    exploded_specification.specification_id,
+                )
+                .await
+                {
+                    Ok(reserved) => reserved,
+                    Err(err) => {
+                        Logger::new(format!("{:?}", err)).log();
+
+                        return Err(Errors::new(&[(
+                            "stock_reservation",
+                            "failed to reserve specification stock",
+                        )]));
+                    }
+                };
+
+                if on_hand - reserved < exploded_specification.quantity {
+                    return Err(Errors::new(&[(
+                        "stock_reservation",
+                        "not enough available stock to reserve",
+                    )]));
+                }
+
+                if let Err(err) = StockReservation::create(
+                    db_transaction,
+                    transaction_item.id,
+                    exploded_specification.specification_id,
+                    exploded_specification.quantity,
+                )
+                .await
+                {
+                    Logger::new(format!("{:?}", err)).log();
+
+                    return Err(Errors::new(&[(
+                        "stock_reservation",
+                        "failed to reserve specification stock",
+                    )]));
+                }
+            }
         }
     }
 
     Ok(transaction.id.to_string())
 }
+
+// post the OUT history of a sold specification, valued with the branch costing method
+async fn process_deduct(
+    db_transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    branch: &Branch,
+    transaction_item_id: Uuid,
+    specification_id: Uuid,
+    quantity: Decimal,
+) -> Result<(), Errors> {
+    let cost = match StockBalance::get_out_cost(
+        db_transaction,
+        &branch.costing_method,
+        specification_id,
+        quantity,
+    )
+    .await
+    {
+        Ok(cost) => cost,
+        Err(err) => {
+            Logger::new(format!("{:?}", err)).log();
+
+            return Err(Errors::new(&[(
+                "specification",
+                "failed to value specification stock",
+            )]));
+        }
+    };
+
+    let price = branch.round_price(cost);
+    let unit_price = branch.round_price(cost.checked_div(quantity).unwrap_or(Decimal::ZERO));
+
+    match SpecificationHistory::create(
+        db_transaction,
+        specification_id,
+        Some(transaction_item_id),
+        Uuid::parse_str("9f175978-100f-431e-97ad-d4f1ab54ba76").unwrap(),
+        None,
+        String::from("OUT"),
+        quantity,
+        price,
+        unit_price,
+        None,
+    )
+    .await
+    {
+        Ok(_) => Ok(()),
+        Err(err) => {
+            Logger::new(format!("{:?}", err)).log();
+
+            Err(Errors::new(&[(
+                "specification_history",
+                "failed to create specification history",
+            )]))
+        }
+    }
+}
+
+// PENDING can move to COMPLETED (taking reserved stock) or CANCELLED (giving back whatever was taken),
+// completed orders are reversed through void or refund instead
+async fn process_update_status(
+    db_transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    branch: &Branch,
+    transaction_id: Uuid,
+    updated_by: Uuid,
+    payload: &RequestUpdateTransactionStatus,
+) -> Result<Transaction, Errors> {
+    let transaction = match Transaction::get_by_id_for_update(db_transaction, branch.id, transaction_id).await {
+        Ok(transaction) => transaction,
+        Err(_) => {
+            return Err(Errors::new(&[(
+                "transaction",
+                "transaction not found or already voided",
+            )]))
+        }
+    };
+
+    if transaction.order_status == "CANCELLED" {
+        return Err(Errors::new(&[(
+            "order_status",
+            "transaction is already cancelled",
+        )]));
+    }
+
+    let payment_status = payload.payment_status.to_owned().unwrap_or(transaction.payment_status.to_owned());
+    let order_status = payload.order_status.to_owned().unwrap_or(transaction.order_status.to_owned());
+
+    if transaction.payment_status == "PAID" && payment_status == "UNPAID" {
+        return Err(Errors::new(&[(
+            "payment_status",
+            "paid transaction cannot go back to unpaid",
+        )]));
+    }
+
+    match (transaction.order_status.as_str(), order_status.as_str()) {
+        (current, next) if current == next => (),
+        ("PENDING", "COMPLETED") => {
+            if payment_status != "PAID" {
+                return Err(Errors::new(&[(
+                    "payment_status",
+                    "transaction must be paid before it is completed",
+                )]));
+            }
+
+            let stock_reservations =
+                match StockReservation::get_reserved_by_transaction_id(db_transaction, transaction_id).await {
+                    Ok(stock_reservations) => stock_reservations,
+                    Err(_) => {
+                        return Err(Errors::new(&[(
+                            "stock_reservation",
+                            "failed to get stock reservations",
+                        )]))
+                    }
+                };
+
+            for stock_reservation in stock_reservations {
+                process_deduct(
+                    db_transaction,
+                    branch,
+                    stock_reservation.transaction_item_id,
+                    stock_reservation.specification_id,
+                    stock_reservation.quantity,
+                )
+                .await?;
+            }
+
+            if StockReservation::update_status_by_transaction_id(db_transaction, transaction_id, "DEDUCTED")
+                .await
+                .is_err()
+            {
+                return Err(Errors::new(&[(
+                    "stock_reservation",
+                    "failed to update stock reservations",
+                )]));
+            }
+        }
+        ("PENDING", "CANCELLED") => {
+            let note = Some(format!("Cancel transaction {}", transaction_id));
+
+            process_reverse_all(db_transaction, branch, transaction_id, updated_by, &note).await?;
+        }
+        ("COMPLETED", _) => {
+            return Err(Errors::new(&[(
+                "order_status",
+                "completed transaction can only be voided or refunded",
+            )]))
+        }
+        _ => {
+            return Err(Errors::new(&[(
+                "order_status",
+                "invalid order status transition",
+            )]))
+        }
+    }
+
+    match Transaction::update_status(db_transaction, transaction_id, order_status, payment_status).await {
+        Ok(transaction) => Ok(transaction),
+        Err(_) => Err(Errors::new(&[(
+            "transaction",
+            "failed to update transaction status",
+        )])),
+    }
+}
+
+// reverse whatever is still unrefunded on every item, then soft delete the transaction
+async fn process_void(
+    db_transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    branch: &Branch,
+    transaction_id: Uuid,
+    voided_by: Uuid,
+    payload: &RequestVoidTransaction,
+) -> Result<(Transaction, Vec<TransactionItem>), Errors> {
+    if Transaction::get_by_id_for_update(db_transaction, branch.id, transaction_id)
+        .await
+        .is_err()
+    {
+        return Err(Errors::new(&[(
+            "transaction",
+            "transaction not found or already voided",
+        )]));
+    }
+
+    let note = Some(format!("Void transaction {}", transaction_id));
+    let voided_items = process_reverse_all(db_transaction, branch, transaction_id, voided_by, &note).await?;
+
+    match Transaction::void(db_transaction, transaction_id, voided_by, payload.reason.to_owned()).await {
+        Ok(transaction) => Ok((transaction, voided_items)),
+        Err(_) => Err(Errors::new(&[(
+            "transaction",
+            "failed to void transaction",
+        )])),
+    }
+}
+
+// release held stock and give back everything still unrefunded on every item
+async fn process_reverse_all(
+    db_transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    branch: &Branch,
+    transaction_id: Uuid,
+    created_by: Uuid,
+    note: &Option<String>,
+) -> Result<Vec<TransactionItem>, Errors> {
+    if StockReservation::update_status_by_transaction_id(db_transaction, transaction_id, "RELEASED")
+        .await
+        .is_err()
+    {
+        return Err(Errors::new(&[(
+            "stock_reservation",
+            "failed to release stock reservations",
+        )]));
+    }
+
+    let items = match TransactionItem::get_by_transaction_id(db_transaction, transaction_id).await {
+        Ok(items) => items,
+        Err(_) => {
+            return Err(Errors::new(&[(
+                "transaction_item",
+                "failed to get transaction items",
+            )]))
+        }
+    };
+
+    let mut voided_items = Vec::<TransactionItem>::new();
+
+    for item in items {
+        let remaining_quantity = item.product_quantity - item.refunded_quantity;
+
+        if remaining_quantity <= Decimal::ZERO {
+            voided_items.push(item);
+            continue;
+        }
+
+        let voided_item = match TransactionItem::refund(db_transaction, item.id, remaining_quantity).await {
+            Ok(voided_item) => voided_item,
+            Err(_) => {
+                return Err(Errors::new(&[(
+                    "transaction_item",
+                    "failed to void transaction item",
+                )]))
+            }
+        };
+
+        process_reverse(db_transaction, branch, &item, remaining_quantity, created_by, note).await?;
+
+        voided_items.push(voided_item);
+    }
+
+    Ok(voided_items)
+}
+
+async fn process_refund(
+    db_transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    branch: &Branch,
+    transaction_id: Uuid,
+    refunded_by: Uuid,
+    payload: &RequestRefundTransaction,
+) -> Result<(Transaction, Vec<TransactionItem>), Errors> {
+    let transaction = match Transaction::get_by_id_for_update(db_transaction, branch.id, transaction_id).await {
+        Ok(transaction) => transaction,
+        Err(_) => {
+            return Err(Errors::new(&[(
+                "transaction",
+                "transaction not found or already voided",
+            )]))
+        }
+    };
+
+    if transaction.order_status != "COMPLETED" {
+        return Err(Errors::new(&[(
+            "order_status",
+            "only completed transactions can be refunded",
+        )]));
+    }
+
+    let items = match TransactionItem::get_by_transaction_id(db_transaction, transaction_id).await {
+        Ok(items) => items,
+        Err(_) => {
+            return Err(Errors::new(&[(
+                "transaction_item",
+                "failed to get transaction items",
+            )]))
+        }
+    };
+
+    let note = match &payload.note {
+        Some(note) => Some(format!("Refund transaction {}: {}", transaction_id, note)),
+        None => Some(format!("Refund transaction {}", transaction_id)),
+    };
+
+    for refund_item in payload.items.iter() {
+        let item = match items.iter().find(|item| item.id == refund_item.transaction_item_id) {
+            Some(item) => item,
+            None => {
+                return Err(Errors::new(&[(
+                    "transaction_item_id",
+                    "transaction item not found in this transaction",
+                )]))
+            }
+        };
+
+        if TransactionItem::refund(db_transaction, item.id, refund_item.product_quantity)
+            .await
+            .is_err()
+        {
+            return Err(Errors::new(&[(
+                "product_quantity",
+                "refund quantity exceeds the quantity left to refund",
+            )]));
+        }
+
+        process_reverse(db_transaction, branch, item, refund_item.product_quantity, refunded_by, &note).await?;
+    }
+
+    match TransactionItem::get_by_transaction_id(db_transaction, transaction_id).await {
+        Ok(items) => Ok((transaction, items)),
+        Err(_) => Err(Errors::new(&[(
+            "transaction_item",
+            "failed to get transaction items",
+        )])),
+    }
+}
+
+// post compensating IN rows for the share of the item being given back, valued at what the OUT rows cost
+async fn process_reverse(
+    db_transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    branch: &Branch,
+    item: &TransactionItem,
+    quantity: Decimal,
+    created_by: Uuid,
+    note: &Option<String>,
+) -> Result<(), Errors> {
+    let specification_histories =
+        match SpecificationHistory::get_out_by_transaction_item_id(db_transaction, item.id).await {
+            Ok(specification_histories) => specification_histories,
+            Err(_) => {
+                return Err(Errors::new(&[(
+                    "specification_history",
+                    "failed to get specification histories",
+                )]))
+            }
+        };
+
+    let ratio = quantity / item.product_quantity;
+
+    for specification_history in specification_histories {
+        let price = branch.round_price(specification_history.price.unwrap_or(Decimal::ZERO) * ratio);
+
+        if SpecificationHistory::create(
+            db_transaction,
+            specification_history.specification_id,
+            Some(item.id),
+            created_by,
+            note.to_owned(),
+            String::from("IN"),
+            (specification_history.quantity * ratio).normalize(),
+            price,
+            specification_history.unit_price.unwrap_or(Decimal::ZERO),
+            None,
+        )
+        .await
+        .is_err()
+        {
+            return Err(Errors::new(&[(
+                "specification_history",
+                "failed to create specification history",
+            )]));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use crate::errors::FieldValidator;
+use crate::models::requests::user::RequestTwoFactorCode;
+use crate::models::responses::DefaultResponse;
+use crate::models::user::User;
+use crate::models::user_recovery_code::UserRecoveryCode;
+use crate::token;
+use crate::totp;
+
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use axum::{extract::State, response::Json};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+// an authenticator code or, failing that, one of the unused recovery codes
+pub async fn check_code(db: &PgPool, user: &User, code: &str) -> bool {
+    let code = code.trim().to_lowercase();
+
+    if let Some(totp_secret) = &user.totp_secret {
+        if let Some(step) = totp::verify(totp_secret, &code, user.totp_last_step) {
+            return User::use_totp_step(db, user.id, step).await.is_ok();
+        }
+    }
+
+    UserRecoveryCode::consume(db, user.id, token::digest(&code))
+        .await
+        .is_ok()
+}
+
+pub async fn setup(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+) -> Response {
+    let user = User::get_by_id(&db, user_id).await.unwrap();
+
+    if user.totp_enabled_at.is_some() {
+        let body = DefaultResponse::error("Two-factor already enabled", Some("Disable two-factor before setting it up again".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let totp_secret = totp::generate_secret();
+    let otpauth_uri = totp::otpauth_uri(&totp_secret, &user.email);
+
+    User::update_totp_secret(&db, user.id, totp_secret.clone())
+        .await
+        .unwrap();
+
+    let body = DefaultResponse::ok("Setup two-factor successfully")
+        .with_data(json!({ "secret": totp_secret, "otpauth_uri": otpauth_uri })).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn enable(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<RequestTwoFactorCode>,
+) -> Response {
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let user = User::get_by_id(&db, user_id).await.unwrap();
+
+    if user.totp_enabled_at.is_some() {
+        let body = DefaultResponse::error("Two-factor already enabled", Some("totp_enabled_at is already set".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let totp_secret = match &user.totp_secret {
+        Some(totp_secret) => totp_secret,
+        None => {
+            let body = DefaultResponse::error("Two-factor not set up", Some("Call two-factor setup first".to_string())).into_json();
+            return (StatusCode::BAD_REQUEST, body).into_response();
+        }
+    };
+
+    // the first code proves the authenticator app holds the secret
+    let step = match totp::verify(totp_secret, payload.code.trim(), None) {
+        Some(step) => step,
+        None => {
+            let body = DefaultResponse::error("Two-factor code is incorrect", None).into_json();
+            return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+        }
+    };
+
+    let recovery_codes = totp::generate_recovery_codes();
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    User::enable_totp(&mut db_transaction, user.id, step)
+        .await
+        .unwrap();
+
+    UserRecoveryCode::replace_by_user_id(
+        &mut db_transaction,
+        user.id,
+        recovery_codes.iter().map(|code| token::digest(code)).collect(),
+    )
+    .await
+    .unwrap();
+
+    db_transaction.commit().await.unwrap();
+
+    // recovery codes are only ever shown here
+    let body = DefaultResponse::ok("Enable two-factor successfully")
+        .with_data(json!({ "recovery_codes": recovery_codes })).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn disable(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<RequestTwoFactorCode>,
+) -> Response {
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let user = User::get_by_id(&db, user_id).await.unwrap();
+
+    if user.totp_enabled_at.is_none() {
+        let body = DefaultResponse::error("Two-factor not enabled", Some("totp_enabled_at is not set".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    if !check_code(&db, &user, &payload.code).await {
+        let body = DefaultResponse::error("Two-factor code is incorrect", None).into_json();
+        return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+    }
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    User::disable_totp(&mut db_transaction, user.id)
+        .await
+        .unwrap();
+
+    UserRecoveryCode::delete_by_user_id(&mut db_transaction, user.id)
+        .await
+        .unwrap();
+
+    db_transaction.commit().await.unwrap();
+
+    let body = DefaultResponse::ok("Disable two-factor successfully").into_json();
+
+    (StatusCode::OK, body).into_response()
+}
+
+pub async fn regenerate_recovery_codes(
+    State(db): State<PgPool>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<RequestTwoFactorCode>,
+) -> Response {
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let user = User::get_by_id(&db, user_id).await.unwrap();
+
+    if user.totp_enabled_at.is_none() {
+        let body = DefaultResponse::error("Two-factor not enabled", Some("totp_enabled_at is not set".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    if !check_code(&db, &user, &payload.code).await {
+        let body = DefaultResponse::error("Two-factor code is incorrect", None).into_json();
+        return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+    }
+
+    let recovery_codes = totp::generate_recovery_codes();
+
+    let mut db_transaction = db.begin().await.unwrap();
+
+    UserRecoveryCode::replace_by_user_id(
+        &mut db_transaction,
+        user.id,
+        recovery_codes.iter().map(|code| token::digest(code)).collect(),
+    )
+    .await
+    .unwrap();
+
+    db_transaction.commit().await.unwrap();
+
+    let body = DefaultResponse::ok("Regenerate recovery codes successfully")
+        .with_data(json!({ "recovery_codes": recovery_codes })).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
@@ -0,0 +1,70 @@
+use crate::errors::FieldValidator;
+use crate::models::branch::Branch;
+use crate::models::requests::unit::RequestFormUnit;
+use crate::models::responses::DefaultResponse;
+use crate::models::unit::Unit;
+
+use axum::extract::Path;
+use axum::response::{IntoResponse, Response};
+use axum::{extract::State, response::Json};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn create(
+    State(db): State<PgPool>,
+    Path((branch_id,)): Path<(Uuid,)>,
+    Json(payload): Json<RequestFormUnit>,
+) -> Response {
+    let branch = Branch::get_by_id(&db, branch_id).await;
+
+    if branch.is_err() {
+        let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    match FieldValidator::validate(&payload).check() {
+        Ok(_) => (),
+        Err(err) => return (StatusCode::UNPROCESSABLE_ENTITY, err.into_response()).into_response(),
+    };
+
+    let unit = Unit::create(
+        &db,
+        branch_id,
+        payload.code.trim().to_lowercase(),
+        payload.name.trim().to_lowercase(),
+        payload.dimension,
+        payload.factor,
+    )
+    .await;
+
+    if unit.is_err() {
+        let body = DefaultResponse::error("Unit already exists", Some("code is duplicate".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let body = DefaultResponse::created("Create unit successfully")
+        .with_data(json!(unit.unwrap())).into_json();
+
+    (StatusCode::CREATED, body).into_response()
+}
+
+pub async fn get_by_branch_id(
+    State(db): State<PgPool>,
+    Path((branch_id,)): Path<(Uuid,)>,
+) -> Response {
+    let branch = Branch::get_by_id(&db, branch_id).await;
+
+    if branch.is_err() {
+        let body = DefaultResponse::error("Branch not found", Some("Branch ID not found".to_string())).into_json();
+        return (StatusCode::BAD_REQUEST, body).into_response();
+    }
+
+    let units = Unit::get_by_branch_id(&db, branch_id).await.unwrap();
+
+    let body = DefaultResponse::ok("Get all units successfully")
+        .with_data(json!(units)).into_json();
+
+    (StatusCode::OK, body).into_response()
+}
@@ -2,8 +2,8 @@ use std::net::SocketAddr;
 
 use axum::{
     http::HeaderValue,
-    routing::{delete, get, post, put},
-    Router,
+    routing::{delete, get, patch, post, put},
+    Extension, Router,
 };
 
 use dotenvy::dotenv;
@@ -15,8 +15,12 @@ mod config;
 mod errors;
 mod handlers;
 mod logger;
+mod mailer;
 mod middlewares;
 mod models;
+mod password;
+mod token;
+mod totp;
 
 pub async fn axum() {
     dotenv().ok();
@@ -34,8 +38,15 @@ pub async fn axum() {
         middlewares::authentication::check_authentication,
     );
 
+    let branch_middleware = axum::middleware::from_fn_with_state(
+        pool.clone(),
+        middlewares::authorization::check_branch_access,
+    );
+
     let check_headers = axum::middleware::from_fn(middlewares::headers::check_headers);
 
+    let mailer = mailer::from_config(&config);
+
     let origins = [
         "http://localhost:3000".parse::<HeaderValue>().unwrap(),
         "https://new-web.maresto.id".parse::<HeaderValue>().unwrap(),
@@ -47,12 +58,39 @@ pub async fn axum() {
     let app = Router::with_state(pool)
         .route(
             "/branches/:id/specification/:id",
-            delete(handlers::specification::delete),
+            delete(handlers::specification::delete)
+                .patch(handlers::specification::update_reorder_point),
         )
         .route(
             "/branches/:id/specification/:id/purchase",
             post(handlers::specification_history::create),
         )
+        .route(
+            "/branches/:id/specification/:id/waste",
+            post(handlers::specification_history::waste),
+        )
+        .route(
+            "/branches/:id/specification/:id/recipe",
+            get(handlers::specification_recipe::get_by_specification_id)
+                .put(handlers::specification_recipe::update),
+        )
+        .route(
+            "/branches/:id/specification/:id/suppliers",
+            get(handlers::supplier::get_by_specification_id)
+                .put(handlers::supplier::set_specification_supplier),
+        )
+        .route(
+            "/branches/:id/suppliers/:id",
+            patch(handlers::supplier::update).delete(handlers::supplier::delete),
+        )
+        .route(
+            "/branches/:id/suppliers",
+            get(handlers::supplier::get_by_branch_id).post(handlers::supplier::create),
+        )
+        .route(
+            "/branches/:id/specifications/:id/balance",
+            get(handlers::specification::balance),
+        )
         .route(
             "/branches/:id/specifications",
             get(handlers::specification::get_by_branch_id).post(handlers::specification::create),
@@ -74,6 +112,114 @@ pub async fn axum() {
             "/branches/:id/transaction",
             post(handlers::transaction::create),
         )
+        .route(
+            "/branches/:id/transactions/:id",
+            get(handlers::transaction::get_by_id),
+        )
+        .route(
+            "/branches/:id/transactions",
+            get(handlers::transaction::get_by_branch_id),
+        )
+        .route(
+            "/branches/:id/transactions/:id/void",
+            post(handlers::transaction::void),
+        )
+        .route(
+            "/branches/:id/transactions/:id/refund",
+            post(handlers::transaction::refund),
+        )
+        .route(
+            "/branches/:id/transactions/:id/status",
+            patch(handlers::transaction::update_status),
+        )
+        .route(
+            "/branches/:id/opnames/:id/items",
+            put(handlers::opname::update_items),
+        )
+        .route(
+            "/branches/:id/opnames/:id/close",
+            post(handlers::opname::close),
+        )
+        .route(
+            "/branches/:id/opnames/:id",
+            get(handlers::opname::get_by_id),
+        )
+        .route(
+            "/branches/:id/opnames",
+            get(handlers::opname::get_by_branch_id).post(handlers::opname::create),
+        )
+        .route(
+            "/branches/:id/purchase-orders/:id/approve",
+            post(handlers::purchase_order::approve),
+        )
+        .route(
+            "/branches/:id/purchase-orders/:id/receive",
+            post(handlers::purchase_order::receive),
+        )
+        .route(
+            "/branches/:id/purchase-orders/:id/close",
+            post(handlers::purchase_order::close),
+        )
+        .route(
+            "/branches/:id/purchase-orders/:id",
+            get(handlers::purchase_order::get_by_id),
+        )
+        .route(
+            "/branches/:id/purchase-orders",
+            get(handlers::purchase_order::get_by_branch_id).post(handlers::purchase_order::create),
+        )
+        .route(
+            "/branches/:id/productions/:id",
+            get(handlers::production::get_by_id),
+        )
+        .route(
+            "/branches/:id/productions",
+            get(handlers::production::get_by_branch_id).post(handlers::production::create),
+        )
+        .route(
+            "/branches/:id/stock-balances/rebuild",
+            post(handlers::stock_balance::rebuild),
+        )
+        .route(
+            "/branches/:id/units",
+            get(handlers::unit::get_by_branch_id).post(handlers::unit::create),
+        )
+        .route(
+            "/branches/:id/alerts/low-stock",
+            get(handlers::alert::low_stock),
+        )
+        .route(
+            "/branches/:id/costing-method",
+            patch(handlers::branch::update_costing_method),
+        )
+        .route(
+            "/branches/:id/stock-deduction",
+            patch(handlers::branch::update_stock_deduction),
+        )
+        .route(
+            "/branches/:id/two-factor",
+            patch(handlers::branch::update_two_factor_roles),
+        )
+        .route(
+            "/branches/:id/rounding-policy",
+            patch(handlers::branch::update_rounding_policy),
+        )
+        .route(
+            "/branches/:id/api-keys/:id",
+            delete(handlers::api_key::delete),
+        )
+        .route(
+            "/branches/:id/api-keys",
+            get(handlers::api_key::get_by_branch_id).post(handlers::api_key::create),
+        )
+        .route(
+            "/branches/:id/members/:id",
+            patch(handlers::branch_member::update).delete(handlers::branch_member::delete),
+        )
+        .route(
+            "/branches/:id/members",
+            get(handlers::branch_member::get_by_branch_id).post(handlers::branch_member::invite),
+        )
         .route("/branches/:id/sync", get(handlers::branch::sync))
         .route(
             "/branches/:id",
@@ -82,11 +228,29 @@ pub async fn axum() {
         .route("/branches", get(handlers::branch::get_by_user_id))
         // .route("/users", get(handlers::user::user_list))
         .route("/branch", post(handlers::branch::create))
+        .route("/logout", post(handlers::session::logout))
+        .route("/sessions/:id", delete(handlers::session::delete))
+        .route("/sessions", get(handlers::session::get_all))
+        .route("/email/resend", post(handlers::email_verification::resend))
+        .route("/two-factor/setup", post(handlers::two_factor::setup))
+        .route("/two-factor/enable", post(handlers::two_factor::enable))
+        .route("/two-factor/disable", post(handlers::two_factor::disable))
+        .route(
+            "/two-factor/recovery-codes",
+            post(handlers::two_factor::regenerate_recovery_codes),
+        )
+        .route_layer(branch_middleware)
         .route_layer(auth_middleware)
         .route("/register", post(handlers::register::register))
+        .route("/login/two-factor", post(handlers::login::login_two_factor))
         .route("/login", post(handlers::login::login))
+        .route("/token/refresh", post(handlers::token::refresh))
+        .route("/password/forgot", post(handlers::password::forgot))
+        .route("/password/reset", post(handlers::password::reset))
+        .route("/email/verify", post(handlers::email_verification::verify))
         .route_layer(check_headers)
         .route("/", get(handlers::user::hello_world))
+        .layer(Extension(mailer))
         .layer(
             CorsLayer::new()
                 .allow_origin(origins)
@@ -106,7 +270,7 @@ pub async fn axum() {
 
     println!("listening on {}", addr);
     axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await
         .unwrap();
 }
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+
+use crate::config::Config;
+use crate::logger::Logger;
+
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+// where outgoing mail ends up, picked with MAIL_DRIVER ( "smtp" or "log" )
+pub trait Mailer: Send + Sync {
+    fn send(&self, mail: &Mail) -> Result<(), String>;
+}
+
+pub struct SmtpMailer {
+    transport: SmtpTransport,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(
+        host: &str,
+        port: u16,
+        username: String,
+        password: String,
+        from: &str,
+    ) -> Result<Self, String> {
+        let transport = SmtpTransport::starttls_relay(host)
+            .map_err(|err| err.to_string())?
+            .port(port)
+            .credentials(Credentials::new(username, password))
+            .build();
+
+        let from = from.parse::<Mailbox>().map_err(|err| err.to_string())?;
+
+        Ok(Self { transport, from })
+    }
+}
+
+impl Mailer for SmtpMailer {
+    fn send(&self, mail: &Mail) -> Result<(), String> {
+        let to = mail.to.parse::<Mailbox>().map_err(|err| err.to_string())?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(mail.subject.clone())
+            .header(ContentType::TEXT_PLAIN)
+            .body(mail.body.clone())
+            .map_err(|err| err.to_string())?;
+
+        self.transport
+            .send(&message)
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+// writes mail into storage/logs instead of sending it, for local development and tests
+pub struct LogMailer;
+
+impl Mailer for LogMailer {
+    fn send(&self, mail: &Mail) -> Result<(), String> {
+        Logger::new(format!("mail to: {}\nsubject: {}\n\n{}", mail.to, mail.subject, mail.body)).log();
+
+        Ok(())
+    }
+}
+
+pub fn from_config(config: &Config) -> Arc<dyn Mailer> {
+    let mail = match config.mail.as_ref() {
+        Some(mail) if mail.driver.as_deref() == Some("smtp") => mail,
+        _ => return Arc::new(LogMailer),
+    };
+
+    let mailer = SmtpMailer::new(
+        mail.host.as_deref().unwrap_or("localhost"),
+        mail.port.unwrap_or(587),
+        mail.username.clone().unwrap_or_default(),
+        mail.password.clone().unwrap_or_default(),
+        mail.from.as_deref().unwrap_or_default(),
+    )
+    .expect("Failed to create smtp mailer");
+
+    Arc::new(mailer)
+}
+
+// smtp blocks, so mail goes out on the blocking pool and failures only end up in the log
+pub fn send_in_background(mailer: Arc<dyn Mailer>, mail: Mail) {
+    tokio::task::spawn_blocking(move || {
+        if let Err(err) = mailer.send(&mail) {
+            Logger::new(format!("failed to send mail to {}: {}", mail.to, err)).log();
+        }
+    });
+}
@@ -8,15 +8,45 @@ use reqwest::StatusCode;
 use sqlx::PgPool;
 
 use crate::models::{
+    api_key::ApiKey,
     oauth_access_token::{self, OauthAccessToken},
     responses::DefaultResponse,
 };
+use crate::token;
 
 pub async fn check_authentication<B>(
     State(db): State<PgPool>,
     mut req: Request<B>,
     next: Next<B>,
 ) -> Response {
+    // machine integrations send a branch api key instead of logging in as a person
+    if let Some(api_key) = req.headers().get("X-Api-Key") {
+        let api_key = match api_key.to_str() {
+            Ok(api_key) => token::digest(api_key),
+            Err(_) => {
+                let body = DefaultResponse::unauthorized("Unauthorized", Some("Invalid format api key".to_string()))
+                    .into_json();
+
+                return (StatusCode::UNAUTHORIZED, body).into_response();
+            }
+        };
+
+        let api_key = match ApiKey::get_by_key_hash(&db, api_key).await {
+            Ok(api_key) => api_key,
+            Err(_) => {
+                let body = DefaultResponse::unauthorized("Unauthorized", Some("Invalid api key".to_string())).into_json();
+
+                return (StatusCode::UNAUTHORIZED, body).into_response();
+            }
+        };
+
+        // records written through the key are attributed to whoever created it
+        req.extensions_mut().insert(api_key.created_by);
+        req.extensions_mut().insert(api_key);
+
+        return next.run(req).await;
+    }
+
     let auth_header = req.headers().get("Authorization");
     if auth_header.is_none() {
         let body = DefaultResponse::unauthorized("Unauthorized", Some("No authorization found".to_string()))
@@ -35,12 +65,12 @@ pub async fn check_authentication<B>(
         return (StatusCode::UNAUTHORIZED, body).into_response();
     }
 
-    let access_token = auth_header[1];
-    let oauth_access_token = match OauthAccessToken::get_by_access_token(&db, access_token).await {
+    let access_token = token::digest(auth_header[1]);
+    let oauth_access_token = match OauthAccessToken::get_by_access_token(&db, &access_token).await {
         Ok(oauth_access_token) => oauth_access_token,
-        Err(err) => {
+        Err(_) => {
             let body =
-                DefaultResponse::unauthorized("Unauthorized", Some(err.to_string()))
+                DefaultResponse::unauthorized("Unauthorized", Some("Invalid or revoked token".to_string()))
                     .into_json();
 
             return (StatusCode::UNAUTHORIZED, body).into_response();
@@ -54,6 +84,7 @@ pub async fn check_authentication<B>(
     }
 
     req.extensions_mut().insert(oauth_access_token.user_id);
+    req.extensions_mut().insert(oauth_access_token);
 
     next.run(req).await
 }
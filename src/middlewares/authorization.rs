@@ -0,0 +1,183 @@
+use axum::{
+    extract::State,
+    http::{Method, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::logger::Logger;
+use crate::models::{api_key::ApiKey, branch_member::BranchMember, responses::DefaultResponse};
+
+// must run after check_authentication, every /branches/:id/... route needs a membership whose role allows it
+pub async fn check_branch_access<B>(
+    State(db): State<PgPool>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let segments = req.uri().path().split('/').collect::<Vec<&str>>();
+
+    // api keys are bound to one branch and their scopes, there is no membership behind them
+    if let Some(api_key) = req.extensions().get::<ApiKey>() {
+        let is_allowed = match segments.as_slice() {
+            ["", "branches", branch_id, resource @ ..] => {
+                Uuid::parse_str(branch_id).is_ok_and(|branch_id| branch_id == api_key.branch_id)
+                    && api_key_scope(req.method(), resource).is_some_and(|scope| api_key.has_scope(&scope))
+            }
+            _ => false,
+        };
+
+        if !is_allowed {
+            let body = DefaultResponse::forbidden("Forbidden", Some("Your api key doesn't allow this action".to_string()))
+                .into_json();
+
+            return (StatusCode::FORBIDDEN, body).into_response();
+        }
+
+        return next.run(req).await;
+    }
+
+    // routes without a branch id in the path (listing and creating branches) are scoped by user_id already
+    let branch_id = match segments.as_slice() {
+        ["", "branches", branch_id, ..] => match Uuid::parse_str(branch_id) {
+            Ok(branch_id) => branch_id,
+            Err(_) => return next.run(req).await,
+        },
+        _ => return next.run(req).await,
+    };
+
+    let user_id = match req.extensions().get::<Uuid>() {
+        Some(user_id) => *user_id,
+        None => {
+            let body = DefaultResponse::unauthorized("Unauthorized", Some("No authenticated user found".to_string()))
+                .into_json();
+
+            return (StatusCode::UNAUTHORIZED, body).into_response();
+        }
+    };
+
+    let access = match BranchMember::get_access(&db, branch_id, user_id).await {
+        Ok(access) => access,
+        Err(err) => {
+            Logger::new(format!("{:?}", err)).log();
+
+            let body = DefaultResponse::error("Something went wrong", Some("Failed to check branch access".to_string())).into_json();
+
+            return (StatusCode::INTERNAL_SERVER_ERROR, body).into_response();
+        }
+    };
+
+    match access {
+        Some(access) if access.two_factor_missing => {
+            let body = DefaultResponse::forbidden("Forbidden", Some("This branch requires two-factor authentication for your role".to_string()))
+                .into_json();
+
+            (StatusCode::FORBIDDEN, body).into_response()
+        }
+        Some(access) if is_allowed(&access.role, req.method(), &segments[3..]) => next.run(req).await,
+        Some(_) => {
+            let body = DefaultResponse::forbidden("Forbidden", Some("Your branch role doesn't allow this action".to_string()))
+                .into_json();
+
+            (StatusCode::FORBIDDEN, body).into_response()
+        }
+        None => {
+            let body = DefaultResponse::forbidden("Forbidden", Some("You don't have access to this branch".to_string()))
+                .into_json();
+
+            (StatusCode::FORBIDDEN, body).into_response()
+        }
+    }
+}
+
+// resource is the path after /branches/:id, e.g. ["opnames", ":id", "close"]
+fn is_allowed(role: &str, method: &Method, resource: &[&str]) -> bool {
+    let is_read = method == Method::GET;
+
+    match role {
+        "OWNER" => true,
+        // everything but membership, api keys and the two-factor policy
+        "MANAGER" => is_read || !matches!(resource.first(), Some(&"members") | Some(&"api-keys") | Some(&"two-factor")),
+        // stock counts, waste and kitchen production, purchases carry prices and stay with managers
+        "STAFF" => {
+            is_read
+                || matches!(resource, ["opnames", ..] | ["productions"] | ["specification", _, "waste"])
+        }
+        "AUDITOR" => is_read,
+        _ => false,
+    }
+}
+
+// scope an api key needs for the resource, settings, members and api keys stay with people
+fn api_key_scope(method: &Method, resource: &[&str]) -> Option<String> {
+    let area = match *resource.first()? {
+        "transaction" | "bulk-transaction" | "transactions" => "transactions",
+        "specification" | "specifications" | "products" | "set-product-specification"
+        | "import-product-specifications" | "stock-balances" | "alerts" | "opnames" | "units"
+        | "suppliers" | "purchase-orders" | "productions" => "stock",
+        _ => return None,
+    };
+
+    let access = if method == Method::GET { "read" } else { "write" };
+
+    Some(format!("{}:{}", area, access))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owner_is_allowed_everything() {
+        assert!(is_allowed("OWNER", &Method::DELETE, &["members", ":id"]));
+        assert!(is_allowed("OWNER", &Method::POST, &["api-keys"]));
+        assert!(is_allowed("OWNER", &Method::PUT, &["two-factor"]));
+    }
+
+    #[test]
+    fn manager_is_kept_from_membership_api_keys_and_two_factor() {
+        assert!(is_allowed("MANAGER", &Method::POST, &["specifications"]));
+        assert!(is_allowed("MANAGER", &Method::POST, &["specification", ":id", "purchase"]));
+        assert!(is_allowed("MANAGER", &Method::GET, &["members"]));
+        assert!(!is_allowed("MANAGER", &Method::POST, &["members"]));
+        assert!(!is_allowed("MANAGER", &Method::DELETE, &["api-keys", ":id"]));
+        assert!(!is_allowed("MANAGER", &Method::PUT, &["two-factor"]));
+    }
+
+    #[test]
+    fn staff_only_writes_counts_waste_and_production() {
+        assert!(is_allowed("STAFF", &Method::GET, &["specifications"]));
+        assert!(is_allowed("STAFF", &Method::POST, &["opnames"]));
+        assert!(is_allowed("STAFF", &Method::POST, &["opnames", ":id", "close"]));
+        assert!(is_allowed("STAFF", &Method::POST, &["productions"]));
+        assert!(is_allowed("STAFF", &Method::POST, &["specification", ":id", "waste"]));
+        assert!(!is_allowed("STAFF", &Method::POST, &["specification", ":id", "purchase"]));
+        assert!(!is_allowed("STAFF", &Method::POST, &["specifications"]));
+        assert!(!is_allowed("STAFF", &Method::POST, &["transaction"]));
+    }
+
+    #[test]
+    fn auditor_and_unknown_roles() {
+        assert!(is_allowed("AUDITOR", &Method::GET, &["transactions"]));
+        assert!(!is_allowed("AUDITOR", &Method::POST, &["opnames"]));
+        assert!(!is_allowed("GUEST", &Method::GET, &["transactions"]));
+    }
+
+    #[test]
+    fn api_key_scope_by_area_and_method() {
+        assert_eq!(api_key_scope(&Method::GET, &["transactions"]), Some("transactions:read".to_string()));
+        assert_eq!(api_key_scope(&Method::POST, &["bulk-transaction"]), Some("transactions:write".to_string()));
+        assert_eq!(api_key_scope(&Method::GET, &["stock-balances"]), Some("stock:read".to_string()));
+        assert_eq!(api_key_scope(&Method::POST, &["specification", ":id", "waste"]), Some("stock:write".to_string()));
+    }
+
+    #[test]
+    fn api_key_scope_leaves_people_only_routes_out() {
+        assert_eq!(api_key_scope(&Method::GET, &["members"]), None);
+        assert_eq!(api_key_scope(&Method::POST, &["api-keys"]), None);
+        assert_eq!(api_key_scope(&Method::PUT, &["two-factor"]), None);
+        assert_eq!(api_key_scope(&Method::GET, &[]), None);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod authentication;
+pub mod authorization;
 pub mod headers;
\ No newline at end of file
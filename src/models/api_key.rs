@@ -0,0 +1,136 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub branch_id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub created_by: Uuid,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+// key_hash is left out of every query, nothing past the lookup needs it
+impl ApiKey {
+    pub async fn create(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+        name: String,
+        prefix: String,
+        key_hash: String,
+        scopes: Vec<String>,
+        created_by: Uuid,
+    ) -> Result<ApiKey, sqlx::Error> {
+        let api_key = sqlx::query_as!(
+            ApiKey,
+            r#"
+            INSERT INTO api_keys (branch_id, name, prefix, key_hash, scopes, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, branch_id, name, prefix, scopes, created_by, last_used_at, created_at, updated_at, deleted_at
+            "#,
+            branch_id,
+            name,
+            prefix,
+            key_hash,
+            &scopes,
+            created_by
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(api_key)
+    }
+
+    // looks the key up and records the use in one round trip, keys of deleted branches stop working
+    // a key only works while whoever created it is still an owner of the branch
+    pub async fn get_by_key_hash(db: &sqlx::PgPool, key_hash: String) -> Result<ApiKey, sqlx::Error> {
+        let api_key = sqlx::query_as!(
+            ApiKey,
+            r#"
+            UPDATE api_keys
+            SET last_used_at = now()
+            WHERE key_hash = $1 AND deleted_at IS NULL
+                AND branch_id IN (SELECT id FROM branches WHERE deleted_at IS NULL)
+                AND EXISTS (
+                    SELECT 1 FROM branch_members bm
+                    WHERE bm.branch_id = api_keys.branch_id AND bm.user_id = api_keys.created_by
+                        AND bm.role = 'OWNER' AND bm.deleted_at IS NULL
+                )
+            RETURNING id, branch_id, name, prefix, scopes, created_by, last_used_at, created_at, updated_at, deleted_at
+            "#,
+            key_hash
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(api_key)
+    }
+
+    pub async fn get_by_id(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+        id: Uuid,
+    ) -> Result<ApiKey, sqlx::Error> {
+        let api_key = sqlx::query_as!(
+            ApiKey,
+            r#"
+            SELECT id, branch_id, name, prefix, scopes, created_by, last_used_at, created_at, updated_at, deleted_at
+            FROM api_keys
+            WHERE id = $1 AND branch_id = $2 AND deleted_at IS NULL
+            "#,
+            id,
+            branch_id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(api_key)
+    }
+
+    pub async fn get_by_branch_id(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+    ) -> Result<Vec<ApiKey>, sqlx::Error> {
+        let api_keys = sqlx::query_as!(
+            ApiKey,
+            r#"
+            SELECT id, branch_id, name, prefix, scopes, created_by, last_used_at, created_at, updated_at, deleted_at
+            FROM api_keys
+            WHERE branch_id = $1 AND deleted_at IS NULL
+            ORDER BY created_at ASC
+            "#,
+            branch_id
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(api_keys)
+    }
+
+    pub async fn delete(db: &sqlx::PgPool, id: Uuid) -> Result<ApiKey, sqlx::Error> {
+        let api_key = sqlx::query_as!(
+            ApiKey,
+            r#"
+            UPDATE api_keys
+            SET deleted_at = now(), updated_at = now()
+            WHERE id = $1
+            RETURNING id, branch_id, name, prefix, scopes, created_by, last_used_at, created_at, updated_at, deleted_at
+            "#,
+            id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(api_key)
+    }
+
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|granted| granted == scope)
+    }
+}
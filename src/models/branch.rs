@@ -1,4 +1,5 @@
 use chrono::NaiveDateTime;
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -11,6 +12,11 @@ pub struct Branch {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub deleted_at: Option<NaiveDateTime>,
+    pub price_scale: i32,
+    pub rounding_mode: String,
+    pub costing_method: String,
+    pub stock_deduction: String,
+    pub two_factor_roles: Vec<String>,
 }
 
 impl Branch {
@@ -72,8 +78,9 @@ impl Branch {
         let branches = sqlx::query_as!(
             Branch,
             r#"
-               SELECT * FROM branches
-               WHERE user_id = $1
+               SELECT b.* FROM branches b
+               INNER JOIN branch_members bm ON bm.branch_id = b.id
+               WHERE bm.user_id = $1 AND bm.deleted_at IS NULL
                "#,
             user_id
         )
@@ -125,6 +132,108 @@ impl Branch {
         Ok(branch)
     }
 
+    pub async fn update_rounding_policy(
+        db: &sqlx::PgPool,
+        id: Uuid,
+        price_scale: i32,
+        rounding_mode: String,
+    ) -> Result<Branch, sqlx::Error> {
+        let branch = sqlx::query_as!(
+            Branch,
+            r#"
+            UPDATE branches
+            SET price_scale = $1, rounding_mode = $2, updated_at = now()
+            WHERE id = $3
+            RETURNING *
+            "#,
+            price_scale,
+            rounding_mode,
+            id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(branch)
+    }
+
+    pub async fn update_costing_method(
+        db: &sqlx::PgPool,
+        id: Uuid,
+        costing_method: String,
+    ) -> Result<Branch, sqlx::Error> {
+        let branch = sqlx::query_as!(
+            Branch,
+            r#"
+            UPDATE branches
+            SET costing_method = $1, updated_at = now()
+            WHERE id = $2
+            RETURNING *
+            "#,
+            costing_method,
+            id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(branch)
+    }
+
+    pub async fn update_stock_deduction(
+        db: &sqlx::PgPool,
+        id: Uuid,
+        stock_deduction: String,
+    ) -> Result<Branch, sqlx::Error> {
+        let branch = sqlx::query_as!(
+            Branch,
+            r#"
+            UPDATE branches
+            SET stock_deduction = $1, updated_at = now()
+            WHERE id = $2
+            RETURNING *
+            "#,
+            stock_deduction,
+            id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(branch)
+    }
+
+    pub async fn update_two_factor_roles(
+        db: &sqlx::PgPool,
+        id: Uuid,
+        two_factor_roles: Vec<String>,
+    ) -> Result<Branch, sqlx::Error> {
+        let branch = sqlx::query_as!(
+            Branch,
+            r#"
+            UPDATE branches
+            SET two_factor_roles = $1, updated_at = now()
+            WHERE id = $2
+            RETURNING *
+            "#,
+            &two_factor_roles,
+            id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(branch)
+    }
+
+    // round a money amount with the rounding policy of the branch
+    pub fn round_price(&self, price: Decimal) -> Decimal {
+        let strategy = match self.rounding_mode.as_str() {
+            "HALF_EVEN" => RoundingStrategy::MidpointNearestEven,
+            "UP" => RoundingStrategy::AwayFromZero,
+            "DOWN" => RoundingStrategy::ToZero,
+            _ => RoundingStrategy::MidpointAwayFromZero,
+        };
+
+        price.round_dp_with_strategy(self.price_scale as u32, strategy)
+    }
+
     //    pub async fn delete(db: &sqlx::PgPool, id: Uuid) -> Result<Branch, sqlx::Error> {
     //        let branch = sqlx::query_as!(
     //            Branch,
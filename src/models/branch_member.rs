@@ -0,0 +1,197 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BranchMember {
+    pub id: Uuid,
+    pub branch_id: Uuid,
+    pub user_id: Uuid,
+    pub role: String,
+    pub invited_by: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BranchMemberWithUser {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub role: String,
+    pub invited_by: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+}
+
+pub struct BranchAccess {
+    pub role: String,
+    // the branch requires two-factor for this role and the user hasn't enabled it
+    pub two_factor_missing: bool,
+}
+
+impl BranchMember {
+    // re-inviting a removed member brings the same row back with the new role
+    pub async fn create(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+        user_id: Uuid,
+        role: String,
+        invited_by: Option<Uuid>,
+    ) -> Result<BranchMember, sqlx::Error> {
+        let branch_member = sqlx::query_as!(
+            BranchMember,
+            r#"
+            INSERT INTO branch_members (branch_id, user_id, role, invited_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (branch_id, user_id) DO UPDATE
+            SET role = EXCLUDED.role,
+                invited_by = EXCLUDED.invited_by,
+                updated_at = now(),
+                deleted_at = NULL
+            WHERE branch_members.deleted_at IS NOT NULL
+            RETURNING *
+            "#,
+            branch_id,
+            user_id,
+            role,
+            invited_by
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(branch_member)
+    }
+
+    pub async fn get_access(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<BranchAccess>, sqlx::Error> {
+        let access = sqlx::query_as!(
+            BranchAccess,
+            r#"
+            SELECT
+                bm.role,
+                (bm.role = ANY(b.two_factor_roles) AND u.totp_enabled_at IS NULL) AS "two_factor_missing!"
+            FROM branch_members bm
+            INNER JOIN branches b ON b.id = bm.branch_id
+            INNER JOIN users u ON u.id = bm.user_id
+            WHERE bm.branch_id = $1 AND bm.user_id = $2 AND bm.deleted_at IS NULL AND b.deleted_at IS NULL
+            "#,
+            branch_id,
+            user_id
+        )
+        .fetch_optional(db)
+        .await?;
+
+        Ok(access)
+    }
+
+    pub async fn get_by_id(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+        id: Uuid,
+    ) -> Result<BranchMember, sqlx::Error> {
+        let branch_member = sqlx::query_as!(
+            BranchMember,
+            r#"
+            SELECT * FROM branch_members
+            WHERE id = $1 AND branch_id = $2 AND deleted_at IS NULL
+            "#,
+            id,
+            branch_id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(branch_member)
+    }
+
+    pub async fn get_by_branch_id(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+    ) -> Result<Vec<BranchMemberWithUser>, sqlx::Error> {
+        let branch_members = sqlx::query_as!(
+            BranchMemberWithUser,
+            r#"
+            SELECT
+                bm.id,
+                bm.user_id,
+                u.name,
+                u.email,
+                bm.role,
+                bm.invited_by,
+                bm.created_at
+            FROM
+                branch_members bm
+                INNER JOIN users u ON u.id = bm.user_id
+            WHERE
+                bm.branch_id = $1
+                AND bm.deleted_at IS NULL
+            ORDER BY
+                bm.created_at ASC
+            "#,
+            branch_id
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(branch_members)
+    }
+
+    pub async fn count_owners(db: &sqlx::PgPool, branch_id: Uuid) -> Result<i64, sqlx::Error> {
+        let total = sqlx::query!(
+            r#"
+            SELECT count(*) AS "total!" FROM branch_members
+            WHERE branch_id = $1 AND role = 'OWNER' AND deleted_at IS NULL
+            "#,
+            branch_id
+        )
+        .fetch_one(db)
+        .await?
+        .total;
+
+        Ok(total)
+    }
+
+    pub async fn update_role(
+        db: &sqlx::PgPool,
+        id: Uuid,
+        role: String,
+    ) -> Result<BranchMember, sqlx::Error> {
+        let branch_member = sqlx::query_as!(
+            BranchMember,
+            r#"
+            UPDATE branch_members
+            SET role = $1, updated_at = now()
+            WHERE id = $2
+            RETURNING *
+            "#,
+            role,
+            id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(branch_member)
+    }
+
+    pub async fn delete(db: &sqlx::PgPool, id: Uuid) -> Result<BranchMember, sqlx::Error> {
+        let branch_member = sqlx::query_as!(
+            BranchMember,
+            r#"
+            UPDATE branch_members
+            SET deleted_at = now(), updated_at = now()
+            WHERE id = $1
+            RETURNING *
+            "#,
+            id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(branch_member)
+    }
+}
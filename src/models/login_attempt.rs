@@ -0,0 +1,168 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LoginAttempt {
+    pub id: Uuid,
+    pub email: String,
+    pub user_id: Option<Uuid>,
+    pub ip_address: String,
+    pub user_agent: Option<String>,
+    pub status: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecentFailures {
+    pub failures: i64,
+    pub last_failed_at: Option<NaiveDateTime>,
+}
+
+impl LoginAttempt {
+    pub async fn create(
+        db: &sqlx::PgPool,
+        email: String,
+        user_id: Option<Uuid>,
+        ip_address: String,
+        user_agent: Option<String>,
+        status: &str,
+    ) -> Result<LoginAttempt, sqlx::Error> {
+        let login_attempt = sqlx::query_as!(
+            LoginAttempt,
+            r#"
+            INSERT INTO login_attempts (email, user_id, ip_address, user_agent, status)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+            email,
+            user_id,
+            ip_address,
+            user_agent,
+            status
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(login_attempt)
+    }
+
+    // failures of the last day that came after the latest successful login, rejected attempts
+    // while locked don't count or the lock would never run out
+    pub async fn get_recent_failures_by_email(
+        db: &sqlx::PgPool,
+        email: String,
+    ) -> Result<RecentFailures, sqlx::Error> {
+        let recent_failures = sqlx::query_as!(
+            RecentFailures,
+            r#"
+            SELECT count(*) AS "failures!", max(created_at) AS last_failed_at
+            FROM login_attempts
+            WHERE email = $1
+                AND status IN ('FAILED', 'TWO_FACTOR_FAILED')
+                AND created_at > now() - INTERVAL '1 day'
+                AND created_at > coalesce(
+                    (SELECT max(created_at) FROM login_attempts WHERE email = $1 AND status = 'SUCCESS'),
+                    '-infinity'
+                )
+            "#,
+            email
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(recent_failures)
+    }
+
+    // one ip trying many emails, a success doesn't reset it since the ip may be shared
+    pub async fn get_recent_failures_by_ip_address(
+        db: &sqlx::PgPool,
+        ip_address: String,
+    ) -> Result<RecentFailures, sqlx::Error> {
+        let recent_failures = sqlx::query_as!(
+            RecentFailures,
+            r#"
+            SELECT count(*) AS "failures!", max(created_at) AS last_failed_at
+            FROM login_attempts
+            WHERE ip_address = $1
+                AND status IN ('FAILED', 'TWO_FACTOR_FAILED')
+                AND created_at > now() - INTERVAL '1 hour'
+            "#,
+            ip_address
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(recent_failures)
+    }
+}
+
+impl RecentFailures {
+    // seconds left to wait, doubling from 2 after free_attempts failures and a flat lockout
+    // from lockout_attempts failures on, counted from the last failure
+    pub fn retry_after(&self, free_attempts: i64, lockout_attempts: i64, lockout_seconds: i64) -> Option<i64> {
+        let last_failed_at = self.last_failed_at?;
+
+        if self.failures < free_attempts {
+            return None;
+        }
+
+        let wait = if self.failures >= lockout_attempts {
+            lockout_seconds
+        } else {
+            2_i64.pow((self.failures - free_attempts + 1) as u32)
+        };
+
+        let remaining = (last_failed_at + chrono::Duration::seconds(wait) - chrono::Utc::now().naive_utc()).num_milliseconds();
+
+        if remaining > 0 {
+            Some((remaining + 999) / 1000)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failures(failures: i64, seconds_ago: i64) -> RecentFailures {
+        RecentFailures {
+            failures,
+            last_failed_at: Some(chrono::Utc::now().naive_utc() - chrono::Duration::seconds(seconds_ago)),
+        }
+    }
+
+    #[test]
+    fn retry_after_is_none_below_free_attempts() {
+        assert_eq!(failures(2, 0).retry_after(3, 10, 900), None);
+    }
+
+    #[test]
+    fn retry_after_is_none_without_failures() {
+        let recent_failures = RecentFailures { failures: 0, last_failed_at: None };
+
+        assert_eq!(recent_failures.retry_after(3, 10, 900), None);
+    }
+
+    #[test]
+    fn retry_after_doubles_from_free_attempts() {
+        assert_eq!(failures(3, 0).retry_after(3, 10, 900), Some(2));
+        assert_eq!(failures(4, 0).retry_after(3, 10, 900), Some(4));
+        assert_eq!(failures(9, 0).retry_after(3, 10, 900), Some(128));
+    }
+
+    #[test]
+    fn retry_after_counts_from_last_failure() {
+        assert_eq!(failures(5, 3).retry_after(3, 10, 900), Some(5));
+        assert_eq!(failures(5, 8).retry_after(3, 10, 900), None);
+    }
+
+    #[test]
+    fn retry_after_locks_out_from_lockout_attempts() {
+        assert_eq!(failures(10, 0).retry_after(3, 10, 900), Some(900));
+        assert_eq!(failures(60, 100).retry_after(3, 10, 900), Some(800));
+        assert_eq!(failures(10, 900).retry_after(3, 10, 900), None);
+    }
+}
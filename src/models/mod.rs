@@ -7,4 +7,18 @@ pub mod specification;
 pub mod specification_history;
 pub mod product_specification;
 pub mod transaction;
-pub mod oauth_access_token;
\ No newline at end of file
+pub mod oauth_access_token;
+pub mod opname;
+pub mod stock_balance;
+pub mod supplier;
+pub mod purchase_order;
+pub mod unit;
+pub mod specification_recipe;
+pub mod production;
+pub mod stock_reservation;
+pub mod branch_member;
+pub mod oauth_refresh_token;
+pub mod api_key;
+pub mod user_token;
+pub mod user_recovery_code;
+pub mod login_attempt;
\ No newline at end of file
@@ -2,7 +2,7 @@ use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OauthAccessToken {
     pub id: Uuid,
     pub access_token: String,
@@ -12,6 +12,16 @@ pub struct OauthAccessToken {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub deleted_at: Option<NaiveDateTime>,
+    pub user_agent: Option<String>,
+    pub session_expires_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
 }
 
 impl OauthAccessToken {
@@ -35,31 +45,79 @@ impl OauthAccessToken {
         Ok(oauth_access_token)
     }
 
+    // a row is one device session, expires_at covers the current access token and
+    // session_expires_at the refresh tokens rotated on it
     pub async fn create(
-        db: &sqlx::PgPool,
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         access_token: String,
         user_id: Uuid,
+        user_agent: Option<String>,
+        expires_at: NaiveDateTime,
+        session_expires_at: NaiveDateTime,
     ) -> Result<OauthAccessToken, sqlx::Error> {
-        // expires at token in 7 days
-        let expires_at = chrono::Utc::now().naive_utc() + chrono::Duration::days(7);
-
         let oauth_access_token = sqlx::query_as!(
             OauthAccessToken,
             r#"
-            INSERT INTO oauth_access_tokens (access_token, user_id, expires_at)
-            VALUES ($1, $2, $3)
+            INSERT INTO oauth_access_tokens (access_token, user_id, expires_at, user_agent, session_expires_at)
+            VALUES ($1, $2, $3, $4, $5)
             RETURNING *
             "#,
             access_token,
             user_id,
-            expires_at
+            expires_at,
+            user_agent,
+            session_expires_at
         )
-        .fetch_one(db)
+        .fetch_one(db_trx)
         .await?;
 
         Ok(oauth_access_token)
     }
 
+    // fails once the session was revoked or outlived its refresh lifetime
+    pub async fn rotate(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+        access_token: String,
+        expires_at: NaiveDateTime,
+    ) -> Result<OauthAccessToken, sqlx::Error> {
+        let oauth_access_token = sqlx::query_as!(
+            OauthAccessToken,
+            r#"
+            UPDATE oauth_access_tokens
+            SET access_token = $1, expires_at = least($2, session_expires_at), updated_at = now()
+            WHERE id = $3 AND revoked_at IS NULL AND session_expires_at > now()
+            RETURNING *
+            "#,
+            access_token,
+            expires_at,
+            id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(oauth_access_token)
+    }
+
+    // a refresh token came back after it was already rotated, drop the whole session
+    pub async fn revoke_session(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE oauth_access_tokens
+            SET revoked_at = now(), updated_at = now()
+            WHERE id = $1 AND revoked_at IS NULL
+            "#,
+            id
+        )
+        .execute(db_trx)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
     pub async fn delete(
         db: &sqlx::PgPool,
         access_token: String,
@@ -81,50 +139,80 @@ impl OauthAccessToken {
         Ok(oauth_access_token)
     }
 
-    pub async fn get_by_user_id(
+    pub async fn revoke_by_id(
         db: &sqlx::PgPool,
+        id: Uuid,
         user_id: Uuid,
     ) -> Result<OauthAccessToken, sqlx::Error> {
-        let oauth_access_tokens = sqlx::query_as!(
+        let oauth_access_token = sqlx::query_as!(
             OauthAccessToken,
             r#"
-            SELECT *
-            FROM oauth_access_tokens
-            WHERE user_id = $1
-            ORDER BY created_at ASC
+            UPDATE oauth_access_tokens
+            SET revoked_at = $1, updated_at = $1
+            WHERE id = $2 AND user_id = $3 AND revoked_at IS NULL
+            RETURNING *
             "#,
+            chrono::Utc::now().naive_utc(),
+            id,
             user_id
         )
         .fetch_one(db)
         .await?;
 
-        Ok(oauth_access_tokens)
+        Ok(oauth_access_token)
+    }
+
+    // keep the newest `keep` active tokens of the user and revoke the rest
+    pub async fn revoke_oldest_by_user_id(
+        db: &sqlx::PgPool,
+        user_id: Uuid,
+        keep: i64,
+    ) -> Result<u64, sqlx::Error> {
+        let now = chrono::Utc::now().naive_utc();
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE oauth_access_tokens
+            SET revoked_at = $1, updated_at = $1
+            WHERE id IN (
+                SELECT id FROM oauth_access_tokens
+                WHERE user_id = $2 AND revoked_at IS NULL AND session_expires_at > $1
+                ORDER BY created_at DESC
+                OFFSET $3
+            )
+            "#,
+            now,
+            user_id,
+            keep
+        )
+        .execute(db)
+        .await?;
+
+        Ok(result.rows_affected())
     }
 
-    pub async fn get_count_by_user_id(
+    pub async fn get_sessions_by_user_id(
         db: &sqlx::PgPool,
         user_id: Uuid,
-    ) -> Result<i64, sqlx::Error> {
-        let count = sqlx::query!(
+    ) -> Result<Vec<Session>, sqlx::Error> {
+        let sessions = sqlx::query_as!(
+            Session,
             r#"
-            SELECT COUNT(*) as count
+            SELECT id, user_agent, session_expires_at AS expires_at, created_at
             FROM oauth_access_tokens
-            WHERE user_id = $1
+            WHERE user_id = $1 AND revoked_at IS NULL AND session_expires_at > $2
+            ORDER BY created_at DESC
             "#,
-            user_id
+            user_id,
+            chrono::Utc::now().naive_utc()
         )
-        .fetch_one(db)
-        .await?
-        .count;
+        .fetch_all(db)
+        .await?;
 
-        match count {
-            Some(count) => Ok(count),
-            None => Ok(0),
-        }
+        Ok(sessions)
     }
 }
 
-
 pub fn is_expired(expires_at: &NaiveDateTime) -> bool {
     let now = chrono::Utc::now().naive_utc();
     now > *expires_at
@@ -0,0 +1,80 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OauthRefreshToken {
+    pub id: Uuid,
+    pub oauth_access_token_id: Uuid,
+    pub refresh_token: String,
+    pub expires_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+impl OauthRefreshToken {
+    pub async fn create(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        oauth_access_token_id: Uuid,
+        refresh_token: String,
+        expires_at: NaiveDateTime,
+    ) -> Result<OauthRefreshToken, sqlx::Error> {
+        let oauth_refresh_token = sqlx::query_as!(
+            OauthRefreshToken,
+            r#"
+            INSERT INTO oauth_refresh_tokens (oauth_access_token_id, refresh_token, expires_at)
+            VALUES ($1, $2, $3)
+            RETURNING *
+            "#,
+            oauth_access_token_id,
+            refresh_token,
+            expires_at
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(oauth_refresh_token)
+    }
+
+    // locked so two refreshes racing with the same token cannot both rotate it
+    pub async fn get_by_refresh_token_for_update(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        refresh_token: String,
+    ) -> Result<OauthRefreshToken, sqlx::Error> {
+        let oauth_refresh_token = sqlx::query_as!(
+            OauthRefreshToken,
+            r#"
+            SELECT * FROM oauth_refresh_tokens
+            WHERE refresh_token = $1 AND deleted_at IS NULL
+            FOR UPDATE
+            "#,
+            refresh_token
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(oauth_refresh_token)
+    }
+
+    pub async fn mark_used(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+    ) -> Result<OauthRefreshToken, sqlx::Error> {
+        let oauth_refresh_token = sqlx::query_as!(
+            OauthRefreshToken,
+            r#"
+            UPDATE oauth_refresh_tokens
+            SET used_at = now(), updated_at = now()
+            WHERE id = $1
+            RETURNING *
+            "#,
+            id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(oauth_refresh_token)
+    }
+}
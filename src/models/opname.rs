@@ -0,0 +1,287 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Opname {
+    pub id: Uuid,
+    pub branch_id: Uuid,
+    pub created_by: Uuid,
+    pub status: String,
+    pub note: Option<String>,
+    pub closed_by: Option<Uuid>,
+    pub closed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OpnameItem {
+    pub id: Uuid,
+    pub opname_id: Uuid,
+    pub specification_id: Uuid,
+    pub system_quantity: Decimal,
+    pub counted_quantity: Option<Decimal>,
+    pub unit_price: Decimal,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OpnameWithItems {
+    pub id: Uuid,
+    pub branch_id: Uuid,
+    pub created_by: Uuid,
+    pub status: String,
+    pub note: Option<String>,
+    pub closed_by: Option<Uuid>,
+    pub closed_at: Option<NaiveDateTime>,
+    pub total_variance_price: Option<Decimal>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+
+    pub items: Option<Vec<SimplifyOpnameItem>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, sqlx::Type)]
+pub struct SimplifyOpnameItem {
+    pub id: Option<Uuid>,
+    pub specification_id: Option<Uuid>,
+    pub specification_name: Option<String>,
+    pub unit_name: Option<String>,
+    pub system_quantity: Option<Decimal>,
+    pub counted_quantity: Option<Decimal>,
+    pub unit_price: Option<Decimal>,
+    pub variance_quantity: Option<Decimal>,
+    pub variance_price: Option<Decimal>,
+}
+
+impl Opname {
+    pub async fn create(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        branch_id: Uuid,
+        created_by: Uuid,
+        note: Option<String>,
+    ) -> Result<Opname, sqlx::Error> {
+        let opname = sqlx::query_as!(
+            Opname,
+            r#"
+            INSERT INTO opnames (branch_id, created_by, note)
+            VALUES ($1, $2, $3)
+            RETURNING *
+            "#,
+            branch_id,
+            created_by,
+            note
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(opname)
+    }
+
+    pub async fn get_by_branch_id(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+    ) -> Result<Vec<Opname>, sqlx::Error> {
+        let opnames = sqlx::query_as!(
+            Opname,
+            r#"
+            SELECT * FROM opnames
+            WHERE branch_id = $1 AND deleted_at IS NULL
+            ORDER BY created_at DESC
+            "#,
+            branch_id
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(opnames)
+    }
+
+    pub async fn get_open_by_branch_id(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+    ) -> Result<Opname, sqlx::Error> {
+        let opname = sqlx::query_as!(
+            Opname,
+            r#"
+            SELECT * FROM opnames
+            WHERE branch_id = $1 AND status = 'OPEN' AND deleted_at IS NULL
+            "#,
+            branch_id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(opname)
+    }
+
+    pub async fn get_by_id_with_items(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+        id: Uuid,
+    ) -> Result<OpnameWithItems, sqlx::Error> {
+        let opname = sqlx::query_as!(
+            OpnameWithItems,
+            r#"
+            SELECT
+                o.id,
+                o.branch_id,
+                o.created_by,
+                o.status,
+                o.note,
+                o.closed_by,
+                o.closed_at,
+                sum((oi.counted_quantity - oi.system_quantity) * oi.unit_price) AS total_variance_price,
+                o.created_at,
+                o.updated_at,
+                coalesce(array_agg((oi.id, oi.specification_id, s.name, s.unit_name, oi.system_quantity, oi.counted_quantity, oi.unit_price, (oi.counted_quantity - oi.system_quantity), ((oi.counted_quantity - oi.system_quantity) * oi.unit_price))
+                ORDER BY
+                    s.name ASC) FILTER (WHERE oi.id IS NOT NULL), '{}') AS "items: Vec<SimplifyOpnameItem>"
+            FROM
+                opnames o
+                LEFT JOIN opname_items oi ON oi.opname_id = o.id
+                LEFT JOIN specifications s ON s.id = oi.specification_id
+            WHERE
+                o.id = $1
+                AND o.branch_id = $2
+                AND o.deleted_at IS NULL
+            GROUP BY
+                o.id
+            "#,
+            id,
+            branch_id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(opname)
+    }
+
+    pub async fn close(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+        closed_by: Uuid,
+    ) -> Result<Opname, sqlx::Error> {
+        let opname = sqlx::query_as!(
+            Opname,
+            r#"
+            UPDATE opnames
+            SET status = 'CLOSED', closed_by = $1, closed_at = now(), updated_at = now()
+            WHERE id = $2 AND status = 'OPEN'
+            RETURNING *
+            "#,
+            closed_by,
+            id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(opname)
+    }
+}
+
+impl OpnameItem {
+    // snapshot the system quantity of every specification in the branch from the stock ledger, reserved
+    // stock is still on the shelf so it stays in the on-hand quantity the count is compared against
+    pub async fn create_snapshot(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        opname_id: Uuid,
+        branch_id: Uuid,
+    ) -> Result<Vec<OpnameItem>, sqlx::Error> {
+        let opname_items = sqlx::query_as!(
+            OpnameItem,
+            r#"
+            INSERT INTO opname_items (opname_id, specification_id, system_quantity, unit_price)
+            SELECT
+                $1,
+                s.id,
+                coalesce(sb.quantity, 0),
+                s.lowest_price
+            FROM
+                specifications s
+                LEFT JOIN stock_balances sb ON sb.specification_id = s.id
+            WHERE
+                s.branch_id = $2
+                AND s.deleted_at IS NULL
+            RETURNING *
+            "#,
+            opname_id,
+            branch_id
+        )
+        .fetch_all(db_trx)
+        .await?;
+
+        Ok(opname_items)
+    }
+
+    pub async fn update_counted_quantity(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        opname_id: Uuid,
+        specification_id: Uuid,
+        counted_quantity: Decimal,
+    ) -> Result<OpnameItem, sqlx::Error> {
+        let opname_item = sqlx::query_as!(
+            OpnameItem,
+            r#"
+            UPDATE opname_items
+            SET counted_quantity = $1, updated_at = now()
+            WHERE opname_id = $2 AND specification_id = $3
+            RETURNING *
+            "#,
+            counted_quantity,
+            opname_id,
+            specification_id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(opname_item)
+    }
+
+    pub async fn get_counted_by_opname_id(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        opname_id: Uuid,
+    ) -> Result<Vec<OpnameItem>, sqlx::Error> {
+        let opname_items = sqlx::query_as!(
+            OpnameItem,
+            r#"
+            SELECT * FROM opname_items
+            WHERE opname_id = $1
+                AND counted_quantity IS NOT NULL
+            "#,
+            opname_id
+        )
+        .fetch_all(db_trx)
+        .await?;
+
+        Ok(opname_items)
+    }
+
+    // the quantity the closing adjustment was measured against, so the reported variance matches the ledger
+    pub async fn update_system_quantity(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+        system_quantity: Decimal,
+    ) -> Result<OpnameItem, sqlx::Error> {
+        let opname_item = sqlx::query_as!(
+            OpnameItem,
+            r#"
+            UPDATE opname_items
+            SET system_quantity = $1, updated_at = now()
+            WHERE id = $2
+            RETURNING *
+            "#,
+            system_quantity,
+            id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(opname_item)
+    }
+}
@@ -1,4 +1,5 @@
 use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -21,7 +22,7 @@ pub struct ProductWithSpecifications {
     pub branch_id: Uuid,
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub cost_of_product: Option<f64>,
+    pub cost_of_product: Option<Decimal>,
     pub reference_id: Uuid,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
@@ -34,7 +35,7 @@ pub struct ProductWithSpecifications {
 pub struct SimplifyProduct {
     pub id: Uuid,
     pub name: String,
-    pub quantity: i32,
+    pub quantity: Decimal,
     pub updated_at: NaiveDateTime,
 }
 
@@ -87,11 +88,11 @@ impl Product {
                 p.id,
                 p.branch_id,
                 p.name,
-                sum(s.lowest_price * ps.quantity) as cost_of_product,
+                sum(specification_unit_cost(s.id) * ps.quantity) as cost_of_product,
                 p.reference_id,
                 p.created_at,
                 p.updated_at,
-                coalesce(array_agg(DISTINCT (s.id, s.name, ps.quantity, s.unit_name, s.lowest_price, (s.lowest_price * ps.quantity))) FILTER (WHERE s.id IS NOT NULL AND s.deleted_at IS NULL), '{}') AS "specifications: Vec<SimplifySpecification>"
+                coalesce(array_agg(DISTINCT (s.id, s.name, ps.quantity, s.unit_name, specification_unit_cost(s.id), (specification_unit_cost(s.id) * ps.quantity))) FILTER (WHERE s.id IS NOT NULL AND s.deleted_at IS NULL), '{}') AS "specifications: Vec<SimplifySpecification>"
             FROM
                 products p
                 LEFT JOIN product_specifications ps ON ps.product_id = p.id
@@ -144,6 +145,7 @@ impl Product {
 
     pub async fn get_by_reference_id_with_specification(
         db: &sqlx::PgPool,
+        branch_id: Uuid,
         reference_id: Uuid,
     ) -> Result<ProductWithSpecifications, sqlx::Error> {
         let product = sqlx::query!(
@@ -152,21 +154,22 @@ impl Product {
                 p.id,
                 p.branch_id,
                 p.name,
-                sum(s.lowest_price * ps.quantity) as cost_of_product,
+                sum(specification_unit_cost(s.id) * ps.quantity) as cost_of_product,
                 p.reference_id,
                 p.created_at,
                 p.updated_at,
-                coalesce(array_agg((s.id, s.name, ps.quantity, s.unit, s.lowest_price, (s.lowest_price * ps.quantity))) FILTER (WHERE s.id IS NOT NULL AND s.deleted_at IS NULL), '{}') AS "specifications: Vec<SimplifySpecification>"
+                coalesce(array_agg((s.id, s.name, ps.quantity, s.unit, specification_unit_cost(s.id), (specification_unit_cost(s.id) * ps.quantity))) FILTER (WHERE s.id IS NOT NULL AND s.deleted_at IS NULL), '{}') AS "specifications: Vec<SimplifySpecification>"
             FROM
                 products p
                 LEFT JOIN product_specifications ps ON ps.product_id = p.id
                 LEFT JOIN specifications s ON s.id = ps.specification_id
-            WHERE p.reference_id = $1 AND p.deleted_at IS NULL
+            WHERE p.reference_id = $1 AND p.branch_id = $2 AND p.deleted_at IS NULL
             GROUP BY
                 p.id
                 ORDER BY p.created_at DESC
                 "#,
-        reference_id
+        reference_id,
+        branch_id
         )
         .map(|record| -> ProductWithSpecifications {
             ProductWithSpecifications {
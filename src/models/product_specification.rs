@@ -1,4 +1,5 @@
 use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -7,7 +8,7 @@ pub struct ProductSpecification {
     pub id: Uuid,
     pub product_id: Uuid,
     pub specification_id: Uuid,
-    pub quantity: i32,
+    pub quantity: Decimal,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub deleted_at: Option<NaiveDateTime>,
@@ -18,7 +19,7 @@ impl ProductSpecification {
         db: &sqlx::PgPool,
         product_id: Uuid,
         specification_id: Uuid,
-        quantity: i32,
+        quantity: Decimal,
     ) -> Result<ProductSpecification, sqlx::Error> {
         let product_specification = sqlx::query_as!(
             ProductSpecification,
@@ -41,7 +42,7 @@ impl ProductSpecification {
         db: &sqlx::PgPool,
         product_id: Uuid,
         specification_id: Uuid,
-        quantity: i32,
+        quantity: Decimal,
     ) -> Result<ProductSpecification, sqlx::Error> {
         let product_specification = sqlx::query_as!(
             ProductSpecification,
@@ -85,7 +86,7 @@ impl ProductSpecification {
         db_transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         product_id: Uuid,
         specification_id: Uuid,
-        quantity: i32,
+        quantity: Decimal,
     ) -> Result<ProductSpecification, sqlx::Error> {
         let product_specification = sqlx::query_as!(
             ProductSpecification,
@@ -109,7 +110,7 @@ impl ProductSpecification {
         product_specification_id: Uuid,
         product_id: Uuid,
         specification_id: Uuid,
-        quantity: i32,
+        quantity: Decimal,
     ) -> Result<ProductSpecification, sqlx::Error> {
         let product_specification = sqlx::query_as!(
             ProductSpecification,
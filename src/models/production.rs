@@ -0,0 +1,210 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Production {
+    pub id: Uuid,
+    pub branch_id: Uuid,
+    pub specification_id: Uuid,
+    pub specification_history_id: Option<Uuid>,
+    pub created_by: Uuid,
+    pub quantity: Decimal,
+    pub total_cost: Decimal,
+    pub unit_cost: Decimal,
+    pub note: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductionItem {
+    pub id: Uuid,
+    pub production_id: Uuid,
+    pub specification_id: Uuid,
+    pub specification_history_id: Uuid,
+    pub quantity: Decimal,
+    pub price: Decimal,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductionWithItems {
+    pub id: Uuid,
+    pub branch_id: Uuid,
+    pub specification_id: Uuid,
+    pub specification_name: String,
+    pub specification_history_id: Option<Uuid>,
+    pub created_by: Uuid,
+    pub quantity: Decimal,
+    pub total_cost: Decimal,
+    pub unit_cost: Decimal,
+    pub note: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+
+    pub items: Option<Vec<SimplifyProductionItem>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, sqlx::Type)]
+pub struct SimplifyProductionItem {
+    pub id: Option<Uuid>,
+    pub specification_id: Option<Uuid>,
+    pub specification_name: Option<String>,
+    pub unit_name: Option<String>,
+    pub quantity: Option<Decimal>,
+    pub price: Option<Decimal>,
+}
+
+impl Production {
+    pub async fn create(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        branch_id: Uuid,
+        specification_id: Uuid,
+        created_by: Uuid,
+        quantity: Decimal,
+        note: Option<String>,
+    ) -> Result<Production, sqlx::Error> {
+        let production = sqlx::query_as!(
+            Production,
+            r#"
+            INSERT INTO productions (branch_id, specification_id, created_by, quantity, note)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+            branch_id,
+            specification_id,
+            created_by,
+            quantity,
+            note
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(production)
+    }
+
+    pub async fn update_output(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+        specification_history_id: Uuid,
+        total_cost: Decimal,
+        unit_cost: Decimal,
+    ) -> Result<Production, sqlx::Error> {
+        let production = sqlx::query_as!(
+            Production,
+            r#"
+            UPDATE productions
+            SET specification_history_id = $1, total_cost = $2, unit_cost = $3, updated_at = now()
+            WHERE id = $4
+            RETURNING *
+            "#,
+            specification_history_id,
+            total_cost,
+            unit_cost,
+            id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(production)
+    }
+
+    pub async fn get_by_branch_id(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+    ) -> Result<Vec<Production>, sqlx::Error> {
+        let productions = sqlx::query_as!(
+            Production,
+            r#"
+            SELECT * FROM productions
+            WHERE branch_id = $1 AND deleted_at IS NULL
+            ORDER BY created_at DESC
+            "#,
+            branch_id
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(productions)
+    }
+
+    pub async fn get_by_id_with_items(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+        id: Uuid,
+    ) -> Result<ProductionWithItems, sqlx::Error> {
+        let production = sqlx::query_as!(
+            ProductionWithItems,
+            r#"
+            SELECT
+                p.id,
+                p.branch_id,
+                p.specification_id,
+                s.name AS specification_name,
+                p.specification_history_id,
+                p.created_by,
+                p.quantity,
+                p.total_cost,
+                p.unit_cost,
+                p.note,
+                p.created_at,
+                p.updated_at,
+                coalesce(array_agg((pi.id, pi.specification_id, cs.name, cs.unit_name, pi.quantity, pi.price)
+                ORDER BY
+                    cs.name ASC) FILTER (WHERE pi.id IS NOT NULL), '{}') AS "items: Vec<SimplifyProductionItem>"
+            FROM
+                productions p
+                INNER JOIN specifications s ON s.id = p.specification_id
+                LEFT JOIN production_items pi ON pi.production_id = p.id
+                LEFT JOIN specifications cs ON cs.id = pi.specification_id
+            WHERE
+                p.id = $1
+                AND p.branch_id = $2
+                AND p.deleted_at IS NULL
+            GROUP BY
+                p.id,
+                s.id
+            "#,
+            id,
+            branch_id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(production)
+    }
+}
+
+impl ProductionItem {
+    pub async fn create(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        production_id: Uuid,
+        specification_id: Uuid,
+        specification_history_id: Uuid,
+        quantity: Decimal,
+        price: Decimal,
+    ) -> Result<ProductionItem, sqlx::Error> {
+        let production_item = sqlx::query_as!(
+            ProductionItem,
+            r#"
+            INSERT INTO production_items (production_id, specification_id, specification_history_id, quantity, price)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+            production_id,
+            specification_id,
+            specification_history_id,
+            quantity,
+            price
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(production_item)
+    }
+}
@@ -0,0 +1,389 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PurchaseOrder {
+    pub id: Uuid,
+    pub branch_id: Uuid,
+    pub supplier_id: Option<Uuid>,
+    pub created_by: Uuid,
+    pub status: String,
+    pub note: Option<String>,
+    pub approved_by: Option<Uuid>,
+    pub approved_at: Option<NaiveDateTime>,
+    pub closed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PurchaseOrderItem {
+    pub id: Uuid,
+    pub purchase_order_id: Uuid,
+    pub specification_id: Uuid,
+    pub quantity: Decimal,
+    pub received_quantity: Decimal,
+    pub unit_price: Decimal,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PurchaseOrderReceipt {
+    pub id: Uuid,
+    pub purchase_order_id: Uuid,
+    pub received_by: Uuid,
+    pub note: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PurchaseOrderReceiptItem {
+    pub id: Uuid,
+    pub purchase_order_receipt_id: Uuid,
+    pub purchase_order_item_id: Uuid,
+    pub specification_history_id: Uuid,
+    pub quantity: Decimal,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PurchaseOrderWithItems {
+    pub id: Uuid,
+    pub branch_id: Uuid,
+    pub supplier_id: Option<Uuid>,
+    pub created_by: Uuid,
+    pub status: String,
+    pub note: Option<String>,
+    pub approved_by: Option<Uuid>,
+    pub approved_at: Option<NaiveDateTime>,
+    pub closed_at: Option<NaiveDateTime>,
+    pub total_price: Option<Decimal>,
+    pub outstanding_quantity: Option<Decimal>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+
+    pub items: Option<Vec<SimplifyPurchaseOrderItem>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, sqlx::Type)]
+pub struct SimplifyPurchaseOrderItem {
+    pub id: Option<Uuid>,
+    pub specification_id: Option<Uuid>,
+    pub specification_name: Option<String>,
+    pub unit_name: Option<String>,
+    pub quantity: Option<Decimal>,
+    pub received_quantity: Option<Decimal>,
+    pub outstanding_quantity: Option<Decimal>,
+    pub unit_price: Option<Decimal>,
+    pub total_price: Option<Decimal>,
+}
+
+impl PurchaseOrder {
+    pub async fn create(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        branch_id: Uuid,
+        supplier_id: Option<Uuid>,
+        created_by: Uuid,
+        note: Option<String>,
+    ) -> Result<PurchaseOrder, sqlx::Error> {
+        let purchase_order = sqlx::query_as!(
+            PurchaseOrder,
+            r#"
+            INSERT INTO purchase_orders (branch_id, supplier_id, created_by, note)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+            branch_id,
+            supplier_id,
+            created_by,
+            note
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(purchase_order)
+    }
+
+    pub async fn get_by_branch_id(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+    ) -> Result<Vec<PurchaseOrder>, sqlx::Error> {
+        let purchase_orders = sqlx::query_as!(
+            PurchaseOrder,
+            r#"
+            SELECT * FROM purchase_orders
+            WHERE branch_id = $1 AND deleted_at IS NULL
+            ORDER BY created_at DESC
+            "#,
+            branch_id
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(purchase_orders)
+    }
+
+    // lock the purchase order so concurrent deliveries cannot over-receive it
+    pub async fn get_by_id_for_update(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        branch_id: Uuid,
+        id: Uuid,
+    ) -> Result<PurchaseOrder, sqlx::Error> {
+        let purchase_order = sqlx::query_as!(
+            PurchaseOrder,
+            r#"
+            SELECT * FROM purchase_orders
+            WHERE id = $1 AND branch_id = $2 AND deleted_at IS NULL
+            FOR UPDATE
+            "#,
+            id,
+            branch_id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(purchase_order)
+    }
+
+    pub async fn get_by_id_with_items(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+        id: Uuid,
+    ) -> Result<PurchaseOrderWithItems, sqlx::Error> {
+        let purchase_order = sqlx::query_as!(
+            PurchaseOrderWithItems,
+            r#"
+            SELECT
+                po.id,
+                po.branch_id,
+                po.supplier_id,
+                po.created_by,
+                po.status,
+                po.note,
+                po.approved_by,
+                po.approved_at,
+                po.closed_at,
+                sum(poi.quantity * poi.unit_price) AS total_price,
+                sum(poi.quantity - poi.received_quantity) AS outstanding_quantity,
+                po.created_at,
+                po.updated_at,
+                coalesce(array_agg((poi.id, poi.specification_id, s.name, s.unit_name, poi.quantity, poi.received_quantity, (poi.quantity - poi.received_quantity), poi.unit_price, (poi.quantity * poi.unit_price))
+                ORDER BY
+                    s.name ASC) FILTER (WHERE poi.id IS NOT NULL), '{}') AS "items: Vec<SimplifyPurchaseOrderItem>"
+            FROM
+                purchase_orders po
+                LEFT JOIN purchase_order_items poi ON poi.purchase_order_id = po.id
+                LEFT JOIN specifications s ON s.id = poi.specification_id
+            WHERE
+                po.id = $1
+                AND po.branch_id = $2
+                AND po.deleted_at IS NULL
+            GROUP BY
+                po.id
+            "#,
+            id,
+            branch_id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(purchase_order)
+    }
+
+    pub async fn approve(
+        db: &sqlx::PgPool,
+        id: Uuid,
+        approved_by: Uuid,
+    ) -> Result<PurchaseOrder, sqlx::Error> {
+        let purchase_order = sqlx::query_as!(
+            PurchaseOrder,
+            r#"
+            UPDATE purchase_orders
+            SET status = 'APPROVED', approved_by = $1, approved_at = now(), updated_at = now()
+            WHERE id = $2 AND status = 'DRAFT'
+            RETURNING *
+            "#,
+            approved_by,
+            id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(purchase_order)
+    }
+
+    pub async fn update_status(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+        status: &str,
+    ) -> Result<PurchaseOrder, sqlx::Error> {
+        let purchase_order = sqlx::query_as!(
+            PurchaseOrder,
+            r#"
+            UPDATE purchase_orders
+            SET status = $1::VARCHAR,
+                closed_at = CASE WHEN $1::VARCHAR = 'CLOSED' THEN now() ELSE closed_at END,
+                updated_at = now()
+            WHERE id = $2
+            RETURNING *
+            "#,
+            status,
+            id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(purchase_order)
+    }
+
+    pub async fn close(db: &sqlx::PgPool, id: Uuid) -> Result<PurchaseOrder, sqlx::Error> {
+        let purchase_order = sqlx::query_as!(
+            PurchaseOrder,
+            r#"
+            UPDATE purchase_orders
+            SET status = 'CLOSED', closed_at = now(), updated_at = now()
+            WHERE id = $1 AND status <> 'CLOSED'
+            RETURNING *
+            "#,
+            id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(purchase_order)
+    }
+}
+
+impl PurchaseOrderItem {
+    pub async fn create(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        purchase_order_id: Uuid,
+        specification_id: Uuid,
+        quantity: Decimal,
+        unit_price: Decimal,
+    ) -> Result<PurchaseOrderItem, sqlx::Error> {
+        let purchase_order_item = sqlx::query_as!(
+            PurchaseOrderItem,
+            r#"
+            INSERT INTO purchase_order_items (purchase_order_id, specification_id, quantity, unit_price)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+            purchase_order_id,
+            specification_id,
+            quantity,
+            unit_price
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(purchase_order_item)
+    }
+
+    // fails when the line does not exist or the delivery exceeds the outstanding quantity
+    pub async fn receive(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        purchase_order_id: Uuid,
+        specification_id: Uuid,
+        quantity: Decimal,
+    ) -> Result<PurchaseOrderItem, sqlx::Error> {
+        let purchase_order_item = sqlx::query_as!(
+            PurchaseOrderItem,
+            r#"
+            UPDATE purchase_order_items
+            SET received_quantity = received_quantity + $1, updated_at = now()
+            WHERE purchase_order_id = $2
+                AND specification_id = $3
+                AND received_quantity + $1 <= quantity
+            RETURNING *
+            "#,
+            quantity,
+            purchase_order_id,
+            specification_id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(purchase_order_item)
+    }
+
+    pub async fn get_outstanding_quantity(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        purchase_order_id: Uuid,
+    ) -> Result<Decimal, sqlx::Error> {
+        let outstanding_quantity = sqlx::query!(
+            r#"
+            SELECT coalesce(sum(quantity - received_quantity), 0) AS outstanding_quantity
+            FROM purchase_order_items
+            WHERE purchase_order_id = $1
+            "#,
+            purchase_order_id
+        )
+        .fetch_one(db_trx)
+        .await?
+        .outstanding_quantity;
+
+        Ok(outstanding_quantity.unwrap_or_default())
+    }
+}
+
+impl PurchaseOrderReceipt {
+    pub async fn create(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        purchase_order_id: Uuid,
+        received_by: Uuid,
+        note: Option<String>,
+    ) -> Result<PurchaseOrderReceipt, sqlx::Error> {
+        let purchase_order_receipt = sqlx::query_as!(
+            PurchaseOrderReceipt,
+            r#"
+            INSERT INTO purchase_order_receipts (purchase_order_id, received_by, note)
+            VALUES ($1, $2, $3)
+            RETURNING *
+            "#,
+            purchase_order_id,
+            received_by,
+            note
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(purchase_order_receipt)
+    }
+}
+
+impl PurchaseOrderReceiptItem {
+    pub async fn create(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        purchase_order_receipt_id: Uuid,
+        purchase_order_item_id: Uuid,
+        specification_history_id: Uuid,
+        quantity: Decimal,
+    ) -> Result<PurchaseOrderReceiptItem, sqlx::Error> {
+        let purchase_order_receipt_item = sqlx::query_as!(
+            PurchaseOrderReceiptItem,
+            r#"
+            INSERT INTO purchase_order_receipt_items (purchase_order_receipt_id, purchase_order_item_id, specification_history_id, quantity)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+            purchase_order_receipt_id,
+            purchase_order_item_id,
+            specification_history_id,
+            quantity
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(purchase_order_receipt_item)
+    }
+}
@@ -0,0 +1,24 @@
+use serde::Deserialize;
+use validator_derive::Validate;
+
+const SCOPES: [&str; 4] = ["transactions:read", "transactions:write", "stock:read", "stock:write"];
+
+#[derive(Deserialize, Validate)]
+pub struct RequestCreateApiKey {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    #[validate(length(min = 1), custom = "validate_scopes")]
+    pub scopes: Vec<String>,
+}
+
+fn validate_scopes(scopes: &[String]) -> Result<(), validator::ValidationError> {
+    if scopes.iter().any(|scope| !SCOPES.contains(&scope.as_str())) {
+        let err = validator::ValidationError::new(
+            "scope invalid ( must transactions:read, transactions:write, stock:read or stock:write )",
+        );
+
+        return Err(err);
+    }
+
+    Ok(())
+}
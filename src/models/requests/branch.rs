@@ -8,3 +8,73 @@ pub struct RequestFormBranch {
     pub name: String,
     pub reference_id: Uuid,
 }
+
+
+#[derive(Deserialize, Validate)]
+pub struct RequestFormRoundingPolicy {
+    #[validate(range(min = 0, max = 6))]
+    pub price_scale: i32,
+    #[validate(custom = "validate_rounding_mode")]
+    pub rounding_mode: String,
+}
+
+fn validate_rounding_mode(rounding_mode: &str) -> Result<(), validator::ValidationError> {
+    if !["HALF_UP", "HALF_EVEN", "UP", "DOWN"].contains(&rounding_mode) {
+        let err = validator::ValidationError::new("rounding mode invalid ( must HALF_UP, HALF_EVEN, UP or DOWN )");
+
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestFormCostingMethod {
+    #[validate(custom = "validate_costing_method")]
+    pub costing_method: String,
+}
+
+fn validate_costing_method(costing_method: &str) -> Result<(), validator::ValidationError> {
+    if costing_method != "WEIGHTED_AVERAGE" && costing_method != "FIFO" {
+        let err = validator::ValidationError::new("costing method invalid ( must WEIGHTED_AVERAGE or FIFO )");
+
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestFormStockDeduction {
+    #[validate(custom = "validate_stock_deduction")]
+    pub stock_deduction: String,
+}
+
+fn validate_stock_deduction(stock_deduction: &str) -> Result<(), validator::ValidationError> {
+    if stock_deduction != "ON_CREATE" && stock_deduction != "ON_COMPLETE" {
+        let err = validator::ValidationError::new("stock deduction invalid ( must ON_CREATE or ON_COMPLETE )");
+
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestFormTwoFactorRoles {
+    #[validate(custom = "validate_two_factor_roles")]
+    pub two_factor_roles: Vec<String>,
+}
+
+fn validate_two_factor_roles(two_factor_roles: &[String]) -> Result<(), validator::ValidationError> {
+    if two_factor_roles
+        .iter()
+        .any(|role| !["OWNER", "MANAGER", "STAFF", "AUDITOR"].contains(&role.as_str()))
+    {
+        let err = validator::ValidationError::new("two factor role invalid ( must OWNER, MANAGER, STAFF or AUDITOR )");
+
+        return Err(err);
+    }
+
+    Ok(())
+}
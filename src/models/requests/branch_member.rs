@@ -0,0 +1,26 @@
+use serde::Deserialize;
+use validator_derive::Validate;
+
+#[derive(Deserialize, Validate)]
+pub struct RequestInviteBranchMember {
+    #[validate(email)]
+    pub email: String,
+    #[validate(custom = "validate_role")]
+    pub role: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestUpdateBranchMember {
+    #[validate(custom = "validate_role")]
+    pub role: String,
+}
+
+fn validate_role(role: &str) -> Result<(), validator::ValidationError> {
+    if !["OWNER", "MANAGER", "STAFF", "AUDITOR"].contains(&role) {
+        let err = validator::ValidationError::new("role invalid ( must OWNER, MANAGER, STAFF or AUDITOR )");
+
+        return Err(err);
+    }
+
+    Ok(())
+}
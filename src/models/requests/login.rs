@@ -8,3 +8,17 @@ pub struct RequestLogin {
     #[validate(length(min = 4))]
     pub password: String,
 }
+
+#[derive(Deserialize, Validate)]
+pub struct RequestRefreshToken {
+    #[validate(length(equal = 64))]
+    pub refresh_token: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestLoginTwoFactor {
+    #[validate(length(equal = 64))]
+    pub two_factor_token: String,
+    #[validate(length(min = 6, max = 11))]
+    pub code: String,
+}
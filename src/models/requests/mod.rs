@@ -3,4 +3,43 @@ pub mod login;
 pub mod branch;
 pub mod specification;
 pub mod product;
-pub mod transaction;
\ No newline at end of file
+pub mod transaction;
+pub mod opname;
+pub mod supplier;
+pub mod purchase_order;
+pub mod unit;
+pub mod production;
+pub mod branch_member;
+pub mod api_key;
+
+use rust_decimal::Decimal;
+
+pub fn validate_positive_quantity(quantity: &Decimal) -> Result<(), validator::ValidationError> {
+    if quantity.is_sign_negative() || quantity.is_zero() {
+        let err = validator::ValidationError::new("quantity must be greater than 0");
+
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+pub fn validate_non_negative_quantity(quantity: &Decimal) -> Result<(), validator::ValidationError> {
+    if quantity.is_sign_negative() && !quantity.is_zero() {
+        let err = validator::ValidationError::new("quantity must be 0 or greater");
+
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+pub fn validate_non_negative_price(price: &Decimal) -> Result<(), validator::ValidationError> {
+    if price.is_sign_negative() && !price.is_zero() {
+        let err = validator::ValidationError::new("price must be 0 or greater");
+
+        return Err(err);
+    }
+
+    Ok(())
+}
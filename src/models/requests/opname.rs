@@ -0,0 +1,24 @@
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use uuid::Uuid;
+use validator_derive::Validate;
+
+use super::validate_non_negative_quantity;
+
+#[derive(Deserialize, Validate)]
+pub struct RequestCreateOpname {
+    #[validate(length(max = 255))]
+    pub note: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestUpdateOpnameItems {
+    pub items: Vec<RequestUpdateOpnameItem>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestUpdateOpnameItem {
+    pub specification_id: Uuid,
+    #[validate(custom = "validate_non_negative_quantity")]
+    pub counted_quantity: Decimal,
+}
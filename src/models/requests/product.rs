@@ -1,11 +1,16 @@
+use rust_decimal::Decimal;
 use serde::Deserialize;
 use uuid::Uuid;
 use validator_derive::Validate;
 
+use super::validate_positive_quantity;
+
 #[derive(Deserialize, Validate)]
 pub struct RequestCreateProductSpecification {
     pub product_id: Uuid,
     pub specification_id: Uuid,
-    #[validate(range(min = 1))]
-    pub quantity: i32,
+    #[validate(custom = "validate_positive_quantity")]
+    pub quantity: Decimal,
+    // unit code the quantity is given in, converted to the specification unit
+    pub unit: Option<String>,
 }
\ No newline at end of file
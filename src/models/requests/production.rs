@@ -0,0 +1,15 @@
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use uuid::Uuid;
+use validator_derive::Validate;
+
+use super::validate_positive_quantity;
+
+#[derive(Deserialize, Validate)]
+pub struct RequestCreateProduction {
+    pub specification_id: Uuid,
+    #[validate(custom = "validate_positive_quantity")]
+    pub quantity: Decimal,
+    #[validate(length(max = 255))]
+    pub note: Option<String>,
+}
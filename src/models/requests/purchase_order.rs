@@ -0,0 +1,37 @@
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use uuid::Uuid;
+use validator_derive::Validate;
+
+use super::{validate_non_negative_price, validate_positive_quantity};
+
+#[derive(Deserialize, Validate)]
+pub struct RequestCreatePurchaseOrder {
+    pub supplier_id: Option<Uuid>,
+    #[validate(length(max = 255))]
+    pub note: Option<String>,
+    pub items: Vec<RequestCreatePurchaseOrderItem>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestCreatePurchaseOrderItem {
+    pub specification_id: Uuid,
+    #[validate(custom = "validate_positive_quantity")]
+    pub quantity: Decimal,
+    #[validate(custom = "validate_non_negative_price")]
+    pub unit_price: Decimal,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestReceivePurchaseOrder {
+    #[validate(length(max = 255))]
+    pub note: Option<String>,
+    pub items: Vec<RequestReceivePurchaseOrderItem>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestReceivePurchaseOrderItem {
+    pub specification_id: Uuid,
+    #[validate(custom = "validate_positive_quantity")]
+    pub quantity: Decimal,
+}
@@ -1,14 +1,32 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::Deserialize;
 use uuid::Uuid;
 use validator_derive::Validate;
 
+use super::{validate_non_negative_price, validate_non_negative_quantity, validate_positive_quantity};
+
 #[derive(Deserialize, Validate)]
 pub struct RequestFormSpecification {
     pub name: String,
     pub smallest_unit: i32,
     pub unit_name: String,
     pub unit: String,
-    pub raw_price: i32,
+    #[validate(custom = "validate_non_negative_price")]
+    pub raw_price: Decimal,
+    #[validate(custom = "validate_non_negative_quantity")]
+    pub min_stock: Option<Decimal>,
+    #[validate(custom = "validate_positive_quantity")]
+    pub reorder_quantity: Option<Decimal>,
+    pub unit_id: Option<Uuid>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestFormReorderPoint {
+    #[validate(custom = "validate_non_negative_quantity")]
+    pub min_stock: Option<Decimal>,
+    #[validate(custom = "validate_positive_quantity")]
+    pub reorder_quantity: Option<Decimal>,
 }
 
 #[derive(Deserialize, Validate)]
@@ -18,12 +36,36 @@ pub struct RequestFormSpecificationHistory {
     #[validate(custom = "validate_flow_specification_history")]
     pub flow_type: String,
     pub note: String,
-    #[validate(range(min = 1))]
-    pub quantity: i32,
-    #[validate(range(min = 10))]
-    pub price: f64,
+    #[validate(custom = "validate_positive_quantity")]
+    pub quantity: Decimal,
+    #[validate(custom = "validate_minimum_price")]
+    pub price: Decimal,
+    pub supplier_id: Option<Uuid>,
+    // unit code the quantity is given in, converted to the specification unit
+    pub unit: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestFormSpecificationRecipe {
+    #[validate(custom = "validate_positive_quantity")]
+    pub recipe_yield: Decimal,
+    // made in production batches instead of being deducted as its components on every sale
+    pub is_prepared: Option<bool>,
+    pub components: Vec<RequestFormSpecificationRecipeComponent>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestFormSpecificationRecipeComponent {
+    pub specification_id: Uuid,
+    #[validate(custom = "validate_positive_quantity")]
+    pub quantity: Decimal,
 }
 
+#[derive(Deserialize)]
+pub struct RequestQueryBalance {
+    // RFC 3339, e.g. 2023-07-01T08:00:00+07:00 or 2023-07-01T01:00:00Z
+    pub at: Option<DateTime<Utc>>,
+}
 
 fn validate_flow_specification_history(transaction_type: &str) -> Result<(), validator::ValidationError> {
     if transaction_type != "IN" && transaction_type != "OUT" {
@@ -34,3 +76,14 @@ fn validate_flow_specification_history(transaction_type: &str) -> Result<(), val
 
     Ok(())
 }
+
+fn validate_minimum_price(price: &Decimal) -> Result<(), validator::ValidationError> {
+    if *price < Decimal::TEN {
+        let mut err = validator::ValidationError::new("range");
+        err.add_param("min".into(), &10);
+
+        return Err(err);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use uuid::Uuid;
+use validator_derive::Validate;
+
+use super::validate_non_negative_price;
+
+#[derive(Deserialize, Validate)]
+pub struct RequestFormSupplier {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    #[validate(length(max = 255))]
+    pub contact: Option<String>,
+    #[validate(range(min = 0))]
+    pub lead_time_days: Option<i32>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestFormSpecificationSupplier {
+    pub supplier_id: Uuid,
+    #[validate(range(min = 1))]
+    pub pack_size: i32,
+    #[validate(custom = "validate_non_negative_price")]
+    pub price: Decimal,
+}
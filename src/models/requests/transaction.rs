@@ -1,11 +1,18 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::Deserialize;
 use uuid::Uuid;
 use validator_derive::Validate;
 
+use super::validate_positive_quantity;
+
 #[derive(Deserialize, Validate)]
 pub struct RequestCreateTransaction {
     pub transaction_id: Option<Uuid>,
+    // a new order can only start as "PENDING" or "COMPLETED", defaults to "COMPLETED"
+    #[validate(custom = "validate_initial_order_status")]
     pub order_status: Option<String>,
+    #[validate(custom = "validate_payment_status")]
     pub payment_status: Option<String>,
     pub items: Vec<RequestCreateTransactionItem>,
     pub created_by: Option<Uuid>,
@@ -15,5 +22,78 @@ pub struct RequestCreateTransaction {
 #[derive(Deserialize, Validate)]
 pub struct RequestCreateTransactionItem {
     pub product_reference_id: Uuid,
-    pub product_quantity: i32,
-}
\ No newline at end of file
+    #[validate(custom = "validate_positive_quantity")]
+    pub product_quantity: Decimal,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestVoidTransaction {
+    #[validate(length(max = 255))]
+    pub reason: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestRefundTransaction {
+    pub items: Vec<RequestRefundTransactionItem>,
+    #[validate(length(max = 255))]
+    pub note: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestRefundTransactionItem {
+    pub transaction_item_id: Uuid,
+    #[validate(custom = "validate_positive_quantity")]
+    pub product_quantity: Decimal,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestUpdateTransactionStatus {
+    #[validate(custom = "validate_order_status")]
+    pub order_status: Option<String>,
+    #[validate(custom = "validate_payment_status")]
+    pub payment_status: Option<String>,
+}
+
+fn validate_initial_order_status(order_status: &str) -> Result<(), validator::ValidationError> {
+    if order_status != "PENDING" && order_status != "COMPLETED" {
+        let err = validator::ValidationError::new("order status invalid ( must PENDING or COMPLETED )");
+
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+fn validate_order_status(order_status: &str) -> Result<(), validator::ValidationError> {
+    if !["PENDING", "COMPLETED", "CANCELLED"].contains(&order_status) {
+        let err = validator::ValidationError::new("order status invalid ( must PENDING, COMPLETED or CANCELLED )");
+
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+fn validate_payment_status(payment_status: &str) -> Result<(), validator::ValidationError> {
+    if payment_status != "UNPAID" && payment_status != "PAID" {
+        let err = validator::ValidationError::new("payment status invalid ( must UNPAID or PAID )");
+
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestQueryTransaction {
+    // RFC 3339, e.g. 2023-07-01T08:00:00+07:00 or 2023-07-01T01:00:00Z
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub product_id: Option<Uuid>,
+    pub created_by: Option<Uuid>,
+    // bounded so (page - 1) * per_page can't overflow the OFFSET
+    #[validate(range(min = 1, max = 1000000))]
+    pub page: Option<i64>,
+    #[validate(range(min = 1, max = 100))]
+    pub per_page: Option<i64>,
+}
@@ -0,0 +1,24 @@
+use serde::Deserialize;
+use validator_derive::Validate;
+
+#[derive(Deserialize, Validate)]
+pub struct RequestFormUnit {
+    #[validate(length(min = 1, max = 50))]
+    pub code: String,
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    #[validate(custom = "validate_unit_dimension")]
+    pub dimension: String,
+    #[validate(range(min = 0.000001))]
+    pub factor: f64,
+}
+
+fn validate_unit_dimension(dimension: &str) -> Result<(), validator::ValidationError> {
+    if dimension != "MASS" && dimension != "VOLUME" && dimension != "COUNT" {
+        let err = validator::ValidationError::new("dimension invalid ( must MASS, VOLUME or COUNT )");
+
+        return Err(err);
+    }
+
+    Ok(())
+}
@@ -10,3 +10,30 @@ pub struct RequestCreateUser {
     #[validate(length(min = 4))]
     pub password: String,
 }
+
+#[derive(Deserialize, Validate)]
+pub struct RequestForgotPassword {
+    #[validate(email)]
+    pub email: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestResetPassword {
+    #[validate(length(equal = 64))]
+    pub token: String,
+    #[validate(length(min = 4))]
+    pub password: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestVerifyEmail {
+    #[validate(length(equal = 64))]
+    pub token: String,
+}
+
+// a 6 digit authenticator code or a recovery code like "1f0c3-9ab27"
+#[derive(Deserialize, Validate)]
+pub struct RequestTwoFactorCode {
+    #[validate(length(min = 6, max = 11))]
+    pub code: String,
+}
@@ -10,6 +10,9 @@ pub struct DefaultResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub access_token: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<serde_json::Value>,
 
@@ -35,6 +38,7 @@ impl DefaultResponse {
             status,
             message,
             access_token: None,
+            refresh_token: None,
             data: None,
             errors: None,
             meta: None
@@ -72,6 +76,13 @@ impl DefaultResponse {
         )
     }
 
+    pub fn forbidden(message: &str, debug: Option<String>) -> Self {
+        Self::new(
+            "forbidden",
+            Message { value: message.to_string(), debug },
+        )
+    }
+
 
     pub fn error(message: &str, debug: Option<String>) -> Self {
         Self::new(
@@ -85,6 +96,11 @@ impl DefaultResponse {
         self
     }
 
+    pub fn with_refresh_token(mut self, refresh_token: String) -> Self {
+        self.refresh_token = Some(refresh_token);
+        self
+    }
+
     //    pub fn with_meta(mut self, meta: serde_json::Value) -> Self {
     //        self.meta = Some(meta);
     //        self
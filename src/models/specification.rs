@@ -1,4 +1,5 @@
 use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -12,8 +13,13 @@ pub struct Specification {
     pub smallest_unit: i32,
     pub unit_name: String,
     pub unit: String,
-    pub lowest_price: f64,
-    pub raw_price: i32,
+    pub lowest_price: Decimal,
+    pub raw_price: Decimal,
+    pub min_stock: Option<Decimal>,
+    pub reorder_quantity: Option<Decimal>,
+    pub unit_id: Option<Uuid>,
+    pub recipe_yield: Option<Decimal>,
+    pub is_prepared: bool,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub deleted_at: Option<NaiveDateTime>,
@@ -27,8 +33,18 @@ pub struct SpecificationWithProduct {
     pub smallest_unit: i32,
     pub unit_name: String,
     pub unit: String,
-    pub lowest_price: f64,
-    pub raw_price: i32,
+    pub lowest_price: Decimal,
+    pub raw_price: Decimal,
+    pub min_stock: Option<Decimal>,
+    pub reorder_quantity: Option<Decimal>,
+    pub unit_id: Option<Uuid>,
+    pub recipe_yield: Option<Decimal>,
+    pub is_prepared: bool,
+    pub unit_cost: Option<Decimal>,
+    pub balance: Option<Decimal>,
+    pub balance_value: Option<Decimal>,
+    pub reserved: Option<Decimal>,
+    pub available: Option<Decimal>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 
@@ -43,13 +59,26 @@ pub struct SimplifySpecification {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub product_specification_quantity: Option<i32>,
+    pub product_specification_quantity: Option<Decimal>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub unit: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub unit_price: Option<f64>,
+    pub unit_price: Option<Decimal>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub product_specification_price: Option<f64>,
+    pub product_specification_price: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LowStockSpecification {
+    pub id: Uuid,
+    pub name: String,
+    pub unit_name: String,
+    pub balance: Option<Decimal>,
+    pub reserved: Option<Decimal>,
+    pub available: Option<Decimal>,
+    pub min_stock: Option<Decimal>,
+    pub reorder_quantity: Option<Decimal>,
+    pub suggested_order_quantity: Option<Decimal>,
 }
 
 impl Specification {
@@ -60,14 +89,17 @@ impl Specification {
         smallest_unit: i32,
         unit_name: String,
         unit: String,
-        lowest_price: f64,
-        raw_price: i32,
+        lowest_price: Decimal,
+        raw_price: Decimal,
+        min_stock: Option<Decimal>,
+        reorder_quantity: Option<Decimal>,
+        unit_id: Option<Uuid>,
     ) -> Result<Specification, sqlx::Error> {
         let specification = sqlx::query_as!(
             Specification,
             r#"
-            INSERT INTO specifications (branch_id, name, smallest_unit, unit_name, unit, lowest_price, raw_price)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO specifications (branch_id, name, smallest_unit, unit_name, unit, lowest_price, raw_price, min_stock, reorder_quantity, unit_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             RETURNING *
             "#,
             branch_id,
@@ -76,7 +108,10 @@ impl Specification {
             unit_name,
             unit,
             lowest_price,
-            raw_price
+            raw_price,
+            min_stock,
+            reorder_quantity,
+            unit_id
         )
         .fetch_one(db)
         .await?;
@@ -91,14 +126,15 @@ impl Specification {
         smallest_unit: i32,
         unit_name: String,
         unit: String,
-        lowest_price: f64,
-        raw_price: i32,
+        lowest_price: Decimal,
+        raw_price: Decimal,
+        unit_id: Option<Uuid>,
     ) -> Result<Specification, sqlx::Error> {
         let specification = sqlx::query_as!(
             Specification,
             r#"
-            INSERT INTO specifications (branch_id, name, smallest_unit, unit_name, unit, lowest_price, raw_price)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO specifications (branch_id, name, smallest_unit, unit_name, unit, lowest_price, raw_price, unit_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             RETURNING *
             "#,
             branch_id,
@@ -107,7 +143,8 @@ impl Specification {
             unit_name,
             unit,
             lowest_price,
-            raw_price
+            raw_price,
+            unit_id
         )
         .fetch_one(db_trx)
         .await?;
@@ -122,15 +159,16 @@ impl Specification {
         smallest_unit: &i32,
         unit_name: &String,
         unit: &String,
-        lowest_price: &f64,
-        raw_price: &i32,
+        lowest_price: &Decimal,
+        raw_price: &Decimal,
+        unit_id: &Option<Uuid>,
     ) -> Result<Specification, sqlx::Error> {
         let specification = sqlx::query_as!(
             Specification,
             r#"
             UPDATE specifications
-            SET smallest_unit = $1, unit_name = $2, unit = $3, lowest_price = $4, raw_price = $5
-            WHERE branch_id = $6 AND name = $7
+            SET smallest_unit = $1, unit_name = $2, unit = $3, lowest_price = $4, raw_price = $5, unit_id = $6
+            WHERE branch_id = $7 AND name = $8
             RETURNING *
             "#,
             smallest_unit,
@@ -138,6 +176,7 @@ impl Specification {
             unit,
             lowest_price,
             raw_price,
+            unit_id.as_ref(),
             branch_id,
             name
         )
@@ -195,6 +234,98 @@ impl Specification {
         Ok(specification)
     }
 
+    pub async fn update_reorder_point(
+        db: &sqlx::PgPool,
+        id: Uuid,
+        min_stock: Option<Decimal>,
+        reorder_quantity: Option<Decimal>,
+    ) -> Result<Specification, sqlx::Error> {
+        let specification = sqlx::query_as!(
+            Specification,
+            r#"
+            UPDATE specifications
+            SET min_stock = $1, reorder_quantity = $2, updated_at = now()
+            WHERE id = $3
+            RETURNING *
+            "#,
+            min_stock,
+            reorder_quantity,
+            id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(specification)
+    }
+
+    pub async fn update_recipe(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+        recipe_yield: Option<Decimal>,
+        is_prepared: bool,
+    ) -> Result<Specification, sqlx::Error> {
+        let specification = sqlx::query_as!(
+            Specification,
+            r#"
+            UPDATE specifications
+            SET recipe_yield = $1, is_prepared = $2, updated_at = now()
+            WHERE id = $3
+            RETURNING *
+            "#,
+            recipe_yield,
+            is_prepared,
+            id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(specification)
+    }
+
+    // compared on available stock, what pending orders reserved is as good as gone, suggest at least
+    // the reorder quantity, or enough to get back to the minimum when that is larger
+    pub async fn get_low_stock_by_branch_id(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+    ) -> Result<Vec<LowStockSpecification>, sqlx::Error> {
+        let specifications = sqlx::query_as!(
+            LowStockSpecification,
+            r#"
+            SELECT
+                s.id,
+                s.name,
+                s.unit_name,
+                coalesce(sb.quantity, 0) AS balance,
+                coalesce(sr.quantity, 0) AS reserved,
+                coalesce(sb.quantity, 0) - coalesce(sr.quantity, 0) AS available,
+                s.min_stock,
+                s.reorder_quantity,
+                greatest(coalesce(s.reorder_quantity, 0), s.min_stock - (coalesce(sb.quantity, 0) - coalesce(sr.quantity, 0))) AS suggested_order_quantity
+            FROM
+                specifications s
+                LEFT JOIN stock_balances sb ON sb.specification_id = s.id
+                LEFT JOIN (
+                    SELECT specification_id, sum(quantity) AS quantity
+                    FROM stock_reservations
+                    WHERE status = 'RESERVED' AND deleted_at IS NULL
+                    GROUP BY specification_id
+                ) sr ON sr.specification_id = s.id
+            WHERE
+                s.branch_id = $1
+                AND s.deleted_at IS NULL
+                AND s.min_stock IS NOT NULL
+                AND coalesce(sb.quantity, 0) - coalesce(sr.quantity, 0) < s.min_stock
+            ORDER BY
+                (coalesce(sb.quantity, 0) - coalesce(sr.quantity, 0) - s.min_stock) ASC
+            "#,
+            branch_id
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(specifications)
+    }
+
     pub async fn delete(db: &sqlx::PgPool, id: Uuid) -> Result<Specification, sqlx::Error> {
         let specification = sqlx::query_as!(
             Specification,
@@ -228,6 +359,16 @@ impl Specification {
                 s.unit,
                 s.lowest_price,
                 s.raw_price,
+                s.min_stock,
+                s.reorder_quantity,
+                s.unit_id,
+                s.recipe_yield,
+                s.is_prepared,
+                specification_unit_cost(s.id) AS unit_cost,
+                (SELECT sb.quantity FROM stock_balances sb WHERE sb.specification_id = s.id) AS balance,
+                (SELECT sb.value FROM stock_balances sb WHERE sb.specification_id = s.id) AS balance_value,
+                coalesce(sr.quantity, 0) AS reserved,
+                coalesce((SELECT sb.quantity FROM stock_balances sb WHERE sb.specification_id = s.id), 0) - coalesce(sr.quantity, 0) AS available,
                 s.created_at,
                 s.updated_at,
                 coalesce(array_agg(DISTINCT (p.id, p.name, ps.quantity, p.updated_at)) FILTER (WHERE p.id IS NOT NULL
@@ -241,11 +382,18 @@ impl Specification {
                 LEFT JOIN product_specifications ps ON ps.specification_id = s.id
                 INNER JOIN products p ON p.id = ps.product_id AND s.id = ps.specification_id
                 LEFT JOIN specification_histories sh ON sh.specification_id = s.id
+                LEFT JOIN (
+                    SELECT specification_id, sum(quantity) AS quantity
+                    FROM stock_reservations
+                    WHERE status = 'RESERVED' AND deleted_at IS NULL
+                    GROUP BY specification_id
+                ) sr ON sr.specification_id = s.id
             WHERE
                 s.branch_id = $1
                 AND s.deleted_at IS NULL
             GROUP BY
-                s.id
+                s.id,
+                sr.quantity
             ORDER BY
                 s.created_at DESC
             "#,
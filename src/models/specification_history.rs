@@ -1,18 +1,22 @@
 use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::stock_balance::StockBalance;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SpecificationHistory {
     pub id: Uuid,
     pub flow_type: String,
     pub specification_id: Uuid,
     pub created_by: Uuid,
-    pub quantity: i32,
+    pub quantity: Decimal,
     pub transaction_item_id: Option<Uuid>,
     pub note: Option<String>,
-    pub price: Option<f64>,
-    pub unit_price: Option<f64>,
+    pub price: Option<Decimal>,
+    pub unit_price: Option<Decimal>,
+    pub supplier_id: Option<Uuid>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub deleted_at: Option<NaiveDateTime>,
@@ -23,12 +27,23 @@ pub struct SimplifySpecificationHistory {
     pub id: Option<Uuid>,
     pub flow_type: Option<String>,
     pub note: Option<String>,
-    pub quantity: Option<i32>,
-    pub price: Option<f64>,
-    pub unit_price: Option<f64>,
+    pub quantity: Option<Decimal>,
+    pub price: Option<Decimal>,
+    pub unit_price: Option<Decimal>,
     pub created_at: Option<NaiveDateTime>,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SpecificationBalance {
+    pub specification_id: Uuid,
+    pub quantity_in: Option<Decimal>,
+    pub quantity_out: Option<Decimal>,
+    pub balance: Option<Decimal>,
+    pub reserved: Option<Decimal>,
+    pub available: Option<Decimal>,
+    pub at: NaiveDateTime,
+}
+
 impl SpecificationHistory {
     pub async fn create(
         db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
@@ -37,15 +52,16 @@ impl SpecificationHistory {
         created_by: Uuid,
         note: Option<String>,
         flow_type: String,
-        quantity: i32,
-        price: f64,
-        unit_price: f64,
+        quantity: Decimal,
+        price: Decimal,
+        unit_price: Decimal,
+        supplier_id: Option<Uuid>,
     ) -> Result<SpecificationHistory, sqlx::Error> {
         let specification_history = sqlx::query_as!(
             SpecificationHistory,
             r#"
-            INSERT INTO specification_histories (flow_type, specification_id, created_by, quantity, transaction_item_id, note, price, unit_price)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            INSERT INTO specification_histories (flow_type, specification_id, created_by, quantity, transaction_item_id, note, price, unit_price, supplier_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             RETURNING *
             "#,
             flow_type,
@@ -55,14 +71,90 @@ impl SpecificationHistory {
             transaction_item_id,
             note,
             price,
-            unit_price
+            unit_price,
+            supplier_id
+        )
+        .fetch_one(&mut *db_trx)
+        .await?;
+
+        StockBalance::apply(
+            db_trx,
+            specification_history.specification_id,
+            &specification_history.flow_type,
+            specification_history.quantity,
+            price,
         )
-        .fetch_one(db_trx)
         .await?;
 
         Ok(specification_history)
     }
 
+    pub async fn get_out_by_transaction_item_id(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        transaction_item_id: Uuid,
+    ) -> Result<Vec<SpecificationHistory>, sqlx::Error> {
+        let specification_histories = sqlx::query_as!(
+            SpecificationHistory,
+            r#"
+            SELECT * FROM specification_histories
+            WHERE transaction_item_id = $1 AND flow_type = 'OUT' AND deleted_at IS NULL
+            ORDER BY created_at ASC
+            "#,
+            transaction_item_id
+        )
+        .fetch_all(db_trx)
+        .await?;
+
+        Ok(specification_histories)
+    }
+
+    // balance is the sum of IN minus the sum of OUT up to and including `at`, available leaves out
+    // what pending orders had reserved at that moment, a reservation stops counting once its status moved on
+    pub async fn get_balance(
+        db: &sqlx::PgPool,
+        specification_id: Uuid,
+        at: NaiveDateTime,
+    ) -> Result<SpecificationBalance, sqlx::Error> {
+        let specification_balance = sqlx::query_as!(
+            SpecificationBalance,
+            r#"
+            SELECT
+                $1::uuid AS "specification_id!",
+                h.quantity_in,
+                h.quantity_out,
+                h.balance,
+                r.reserved,
+                h.balance - r.reserved AS available,
+                $2::timestamp AS "at!"
+            FROM
+                (
+                    SELECT
+                        coalesce(sum(quantity) FILTER (WHERE flow_type = 'IN'), 0) AS quantity_in,
+                        coalesce(sum(quantity) FILTER (WHERE flow_type = 'OUT'), 0) AS quantity_out,
+                        coalesce(sum(CASE WHEN flow_type = 'IN' THEN quantity ELSE -quantity END), 0) AS balance
+                    FROM specification_histories
+                    WHERE specification_id = $1
+                        AND created_at <= $2
+                        AND deleted_at IS NULL
+                ) h,
+                (
+                    SELECT coalesce(sum(quantity), 0) AS reserved
+                    FROM stock_reservations
+                    WHERE specification_id = $1
+                        AND created_at <= $2
+                        AND deleted_at IS NULL
+                        AND (status = 'RESERVED' OR updated_at > $2)
+                ) r
+            "#,
+            specification_id,
+            at
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(specification_balance)
+    }
+
 //    pub async fn get_all(db: &sqlx::PgPool) -> Result<Vec<SpecificationHistory>, sqlx::Error> {
 //        let specification_histories = sqlx::query_as!(
 //            SpecificationHistory,
@@ -0,0 +1,195 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SpecificationRecipe {
+    pub id: Uuid,
+    pub specification_id: Uuid,
+    pub component_specification_id: Uuid,
+    pub quantity: Decimal,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SpecificationRecipeComponent {
+    pub component_specification_id: Uuid,
+    pub name: String,
+    pub unit_name: String,
+    pub quantity: Decimal,
+    pub unit_cost: Option<Decimal>,
+    pub total_cost: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExplodedSpecification {
+    pub specification_id: Uuid,
+    pub quantity: Decimal,
+    pub unit_price: Decimal,
+}
+
+impl SpecificationRecipe {
+    pub async fn create(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        specification_id: Uuid,
+        component_specification_id: Uuid,
+        quantity: Decimal,
+    ) -> Result<SpecificationRecipe, sqlx::Error> {
+        let specification_recipe = sqlx::query_as!(
+            SpecificationRecipe,
+            r#"
+            INSERT INTO specification_recipes (specification_id, component_specification_id, quantity)
+            VALUES ($1, $2, $3)
+            RETURNING *
+            "#,
+            specification_id,
+            component_specification_id,
+            quantity
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(specification_recipe)
+    }
+
+    pub async fn delete_by_specification_id(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        specification_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE specification_recipes
+            SET deleted_at = now(), updated_at = now()
+            WHERE specification_id = $1 AND deleted_at IS NULL
+            "#,
+            specification_id
+        )
+        .execute(db_trx)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    // true when the specification shows up again somewhere below itself in its recipe tree
+    pub async fn has_cycle(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        specification_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let has_cycle = sqlx::query!(
+            r#"
+            WITH RECURSIVE tree AS (
+                SELECT sr.component_specification_id AS specification_id, ARRAY[sr.specification_id] AS path
+                FROM specification_recipes sr
+                WHERE sr.specification_id = $1 AND sr.deleted_at IS NULL
+                UNION ALL
+                SELECT sr.component_specification_id, t.path || t.specification_id
+                FROM
+                    tree t
+                    INNER JOIN specification_recipes sr ON sr.specification_id = t.specification_id AND sr.deleted_at IS NULL
+                WHERE NOT t.specification_id = ANY(t.path)
+            )
+            SELECT EXISTS (SELECT 1 FROM tree WHERE specification_id = $1) AS "has_cycle!"
+            "#,
+            specification_id
+        )
+        .fetch_one(db_trx)
+        .await?
+        .has_cycle;
+
+        Ok(has_cycle)
+    }
+
+    pub async fn get_by_specification_id(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        specification_id: Uuid,
+    ) -> Result<Vec<SpecificationRecipe>, sqlx::Error> {
+        let specification_recipes = sqlx::query_as!(
+            SpecificationRecipe,
+            r#"
+            SELECT * FROM specification_recipes
+            WHERE specification_id = $1 AND deleted_at IS NULL
+            "#,
+            specification_id
+        )
+        .fetch_all(db_trx)
+        .await?;
+
+        Ok(specification_recipes)
+    }
+
+    pub async fn get_components_by_specification_id(
+        db: &sqlx::PgPool,
+        specification_id: Uuid,
+    ) -> Result<Vec<SpecificationRecipeComponent>, sqlx::Error> {
+        let components = sqlx::query_as!(
+            SpecificationRecipeComponent,
+            r#"
+            SELECT
+                sr.component_specification_id,
+                s.name,
+                s.unit_name,
+                sr.quantity,
+                specification_unit_cost(s.id) AS unit_cost,
+                (specification_unit_cost(s.id) * sr.quantity) AS total_cost
+            FROM
+                specification_recipes sr
+                INNER JOIN specifications s ON s.id = sr.component_specification_id
+            WHERE
+                sr.specification_id = $1
+                AND sr.deleted_at IS NULL
+            ORDER BY
+                s.name ASC
+            "#,
+            specification_id
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(components)
+    }
+
+    // walk the recipe tree down to the raw or prepared specifications that actually leave the stock
+    pub async fn explode(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        specification_id: Uuid,
+        quantity: Decimal,
+    ) -> Result<Vec<ExplodedSpecification>, sqlx::Error> {
+        let exploded_specifications = sqlx::query_as!(
+            ExplodedSpecification,
+            r#"
+            WITH RECURSIVE tree AS (
+                SELECT $1::uuid AS specification_id, $2::NUMERIC AS quantity, ARRAY[$1::uuid] AS path
+                UNION ALL
+                SELECT sr.component_specification_id, t.quantity * sr.quantity / coalesce(s.recipe_yield, 1), t.path || sr.component_specification_id
+                FROM
+                    tree t
+                    INNER JOIN specifications s ON s.id = t.specification_id AND NOT s.is_prepared
+                    INNER JOIN specification_recipes sr ON sr.specification_id = t.specification_id AND sr.deleted_at IS NULL
+                WHERE NOT sr.component_specification_id = ANY(t.path)
+            )
+            SELECT
+                t.specification_id AS "specification_id!",
+                sum(t.quantity) AS "quantity!",
+                specification_unit_cost(s.id) AS "unit_price!"
+            FROM
+                tree t
+                INNER JOIN specifications s ON s.id = t.specification_id
+            WHERE
+                s.is_prepared
+                OR NOT EXISTS (SELECT 1 FROM specification_recipes sr WHERE sr.specification_id = t.specification_id AND sr.deleted_at IS NULL)
+            GROUP BY
+                t.specification_id,
+                s.id
+            "#,
+            specification_id,
+            quantity
+        )
+        .fetch_all(db_trx)
+        .await?;
+
+        Ok(exploded_specifications)
+    }
+}
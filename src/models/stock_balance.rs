@@ -0,0 +1,250 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StockBalance {
+    pub id: Uuid,
+    pub specification_id: Uuid,
+    pub quantity: Decimal,
+    pub value: Decimal,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StockBalanceDrift {
+    pub specification_id: Uuid,
+    pub specification_name: String,
+    pub stored_quantity: Option<Decimal>,
+    pub computed_quantity: Option<Decimal>,
+    pub stored_value: Option<Decimal>,
+    pub computed_value: Option<Decimal>,
+}
+
+impl StockBalance {
+    // must run inside the same db transaction as the specification history it mirrors
+    pub async fn apply(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        specification_id: Uuid,
+        flow_type: &str,
+        quantity: Decimal,
+        price: Decimal,
+    ) -> Result<StockBalance, sqlx::Error> {
+        let (quantity, value) = match flow_type {
+            "IN" => (quantity, price),
+            _ => (-quantity, -price),
+        };
+
+        let stock_balance = sqlx::query_as!(
+            StockBalance,
+            r#"
+            INSERT INTO stock_balances (specification_id, quantity, value)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (specification_id) DO UPDATE
+            SET quantity = stock_balances.quantity + EXCLUDED.quantity,
+                value = stock_balances.value + EXCLUDED.value,
+                updated_at = now()
+            RETURNING *
+            "#,
+            specification_id,
+            quantity,
+            value
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(stock_balance)
+    }
+
+    // none until the specification has its first movement
+    pub async fn get_by_specification_id_for_update(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        specification_id: Uuid,
+    ) -> Result<Option<StockBalance>, sqlx::Error> {
+        let stock_balance = sqlx::query_as!(
+            StockBalance,
+            r#"
+            SELECT * FROM stock_balances
+            WHERE specification_id = $1
+            FOR UPDATE
+            "#,
+            specification_id
+        )
+        .fetch_optional(db_trx)
+        .await?;
+
+        Ok(stock_balance)
+    }
+
+    // value an OUT movement from the IN rows on hand, the balance row is locked so concurrent
+    // movements of the same specification cannot consume the same layers. refund and void IN rows
+    // (the only IN rows tied to a transaction item) are not layers of their own, they give the
+    // consumed quantity back so the next OUT takes those units again at the cost they left with
+    pub async fn get_out_cost(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        costing_method: &str,
+        specification_id: Uuid,
+        quantity: Decimal,
+    ) -> Result<Decimal, sqlx::Error> {
+        let stock_balance = StockBalance::get_by_specification_id_for_update(db_trx, specification_id).await?;
+
+        let (covered_quantity, covered_cost) = match costing_method {
+            "FIFO" => {
+                let layers = sqlx::query!(
+                    r#"
+                    WITH layers AS (
+                        SELECT
+                            sh.quantity,
+                            coalesce(sh.unit_price, 0) AS unit_price,
+                            sum(sh.quantity) OVER (ORDER BY sh.created_at, sh.id) AS cumulative_quantity
+                        FROM specification_histories sh
+                        WHERE
+                            sh.specification_id = $1
+                            AND sh.flow_type = 'IN'
+                            AND sh.transaction_item_id IS NULL
+                            AND sh.deleted_at IS NULL
+                    ),
+                    consumed AS (
+                        SELECT coalesce(sum(CASE WHEN sh.flow_type = 'OUT' THEN sh.quantity ELSE -sh.quantity END), 0) AS quantity
+                        FROM specification_histories sh
+                        WHERE
+                            sh.specification_id = $1
+                            AND (sh.flow_type = 'OUT' OR sh.transaction_item_id IS NOT NULL)
+                            AND sh.deleted_at IS NULL
+                    ),
+                    taken AS (
+                        SELECT
+                            least(l.cumulative_quantity, c.quantity + $2) - greatest(l.cumulative_quantity - l.quantity, c.quantity) AS quantity,
+                            l.unit_price
+                        FROM layers l, consumed c
+                        WHERE l.cumulative_quantity > c.quantity AND l.cumulative_quantity - l.quantity < c.quantity + $2
+                    )
+                    SELECT
+                        coalesce(sum(t.quantity), 0) AS "quantity!",
+                        coalesce(sum(t.quantity * t.unit_price), 0) AS "cost!"
+                    FROM taken t
+                    "#,
+                    specification_id,
+                    quantity
+                )
+                .fetch_one(&mut *db_trx)
+                .await?;
+
+                (layers.quantity, layers.cost)
+            }
+            _ => match stock_balance {
+                Some(stock_balance) if stock_balance.quantity > Decimal::ZERO => {
+                    (quantity, quantity * stock_balance.value / stock_balance.quantity)
+                }
+                _ => (Decimal::ZERO, Decimal::ZERO),
+            },
+        };
+
+        if covered_quantity >= quantity {
+            return Ok(covered_cost);
+        }
+
+        // nothing left on hand for the rest, fall back to the latest purchase then to the recipe cost
+        let fallback_unit_price = sqlx::query!(
+            r#"
+            SELECT coalesce(
+                (
+                    SELECT sh.unit_price FROM specification_histories sh
+                    WHERE sh.specification_id = $1 AND sh.flow_type = 'IN' AND sh.transaction_item_id IS NULL AND sh.deleted_at IS NULL
+                    ORDER BY sh.created_at DESC
+                    LIMIT 1
+                ),
+                specification_unit_cost($1),
+                0
+            ) AS "unit_price!"
+            "#,
+            specification_id
+        )
+        .fetch_one(&mut *db_trx)
+        .await?
+        .unit_price;
+
+        Ok(covered_cost + (quantity - covered_quantity) * fallback_unit_price)
+    }
+
+    pub async fn get_drifts_by_branch_id(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        branch_id: Uuid,
+    ) -> Result<Vec<StockBalanceDrift>, sqlx::Error> {
+        let drifts = sqlx::query_as!(
+            StockBalanceDrift,
+            r#"
+            WITH computed AS (
+                SELECT
+                    s.id AS specification_id,
+                    coalesce(sum(CASE WHEN sh.flow_type = 'IN' THEN sh.quantity ELSE -sh.quantity END), 0) AS quantity,
+                    coalesce(sum(CASE WHEN sh.flow_type = 'IN' THEN coalesce(sh.price, 0) ELSE -coalesce(sh.price, 0) END), 0) AS value
+                FROM
+                    specifications s
+                    LEFT JOIN specification_histories sh ON sh.specification_id = s.id AND sh.deleted_at IS NULL
+                WHERE
+                    s.branch_id = $1
+                GROUP BY
+                    s.id
+            )
+            SELECT
+                s.id AS specification_id,
+                s.name AS specification_name,
+                sb.quantity AS "stored_quantity?",
+                c.quantity AS computed_quantity,
+                sb.value AS "stored_value?",
+                c.value AS computed_value
+            FROM
+                computed c
+                INNER JOIN specifications s ON s.id = c.specification_id
+                LEFT JOIN stock_balances sb ON sb.specification_id = c.specification_id
+            WHERE
+                sb.id IS NULL
+                OR sb.quantity <> c.quantity
+                OR abs(sb.value - c.value) >= 0.01
+            ORDER BY
+                s.name ASC
+            "#,
+            branch_id
+        )
+        .fetch_all(db_trx)
+        .await?;
+
+        Ok(drifts)
+    }
+
+    pub async fn rebuild_by_branch_id(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        branch_id: Uuid,
+    ) -> Result<Vec<StockBalance>, sqlx::Error> {
+        let stock_balances = sqlx::query_as!(
+            StockBalance,
+            r#"
+            INSERT INTO stock_balances (specification_id, quantity, value)
+            SELECT
+                s.id,
+                coalesce(sum(CASE WHEN sh.flow_type = 'IN' THEN sh.quantity ELSE -sh.quantity END), 0),
+                coalesce(sum(CASE WHEN sh.flow_type = 'IN' THEN coalesce(sh.price, 0) ELSE -coalesce(sh.price, 0) END), 0)
+            FROM
+                specifications s
+                LEFT JOIN specification_histories sh ON sh.specification_id = s.id AND sh.deleted_at IS NULL
+            WHERE
+                s.branch_id = $1
+            GROUP BY
+                s.id
+            ON CONFLICT (specification_id) DO UPDATE
+            SET quantity = EXCLUDED.quantity,
+                value = EXCLUDED.value,
+                updated_at = now()
+            RETURNING *
+            "#,
+            branch_id
+        )
+        .fetch_all(db_trx)
+        .await?;
+
+        Ok(stock_balances)
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StockReservation {
+    pub id: Uuid,
+    pub transaction_item_id: Uuid,
+    pub specification_id: Uuid,
+    pub quantity: Decimal,
+    pub status: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+impl StockReservation {
+    pub async fn create(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        transaction_item_id: Uuid,
+        specification_id: Uuid,
+        quantity: Decimal,
+    ) -> Result<StockReservation, sqlx::Error> {
+        let stock_reservation = sqlx::query_as!(
+            StockReservation,
+            r#"
+            INSERT INTO stock_reservations (transaction_item_id, specification_id, quantity)
+            VALUES ($1, $2, $3)
+            RETURNING *
+            "#,
+            transaction_item_id,
+            specification_id,
+            quantity
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(stock_reservation)
+    }
+
+    // stock promised to pending orders, still on hand but no longer available
+    pub async fn get_reserved_quantity_by_specification_id(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        specification_id: Uuid,
+    ) -> Result<Decimal, sqlx::Error> {
+        let reserved = sqlx::query!(
+            r#"
+            SELECT coalesce(sum(quantity), 0) AS "quantity!"
+            FROM stock_reservations
+            WHERE specification_id = $1 AND status = 'RESERVED' AND deleted_at IS NULL
+            "#,
+            specification_id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(reserved.quantity)
+    }
+
+    pub async fn get_reserved_by_transaction_id(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        transaction_id: Uuid,
+    ) -> Result<Vec<StockReservation>, sqlx::Error> {
+        let stock_reservations = sqlx::query_as!(
+            StockReservation,
+            r#"
+            SELECT sr.* FROM stock_reservations sr
+            INNER JOIN transaction_items ti ON ti.id = sr.transaction_item_id
+            WHERE ti.transaction_id = $1 AND sr.status = 'RESERVED' AND sr.deleted_at IS NULL
+            ORDER BY sr.created_at ASC
+            "#,
+            transaction_id
+        )
+        .fetch_all(db_trx)
+        .await?;
+
+        Ok(stock_reservations)
+    }
+
+    // status is "DEDUCTED" once the stock actually left or "RELEASED" when the order was dropped
+    pub async fn update_status_by_transaction_id(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        transaction_id: Uuid,
+        status: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE stock_reservations sr
+            SET status = $1, updated_at = now()
+            FROM transaction_items ti
+            WHERE ti.id = sr.transaction_item_id AND ti.transaction_id = $2 AND sr.status = 'RESERVED'
+            "#,
+            status,
+            transaction_id
+        )
+        .execute(db_trx)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,227 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Supplier {
+    pub id: Uuid,
+    pub branch_id: Uuid,
+    pub name: String,
+    pub contact: Option<String>,
+    pub lead_time_days: Option<i32>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SpecificationSupplier {
+    pub id: Uuid,
+    pub specification_id: Uuid,
+    pub supplier_id: Uuid,
+    pub pack_size: i32,
+    pub price: Decimal,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SpecificationSupplierComparison {
+    pub supplier_id: Uuid,
+    pub supplier_name: String,
+    pub lead_time_days: Option<i32>,
+    pub pack_size: i32,
+    pub price: Decimal,
+    pub unit_price: Option<Decimal>,
+    pub last_purchase_unit_price: Option<Decimal>,
+    pub purchased_quantity: Option<Decimal>,
+}
+
+impl Supplier {
+    pub async fn create(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+        name: String,
+        contact: Option<String>,
+        lead_time_days: Option<i32>,
+    ) -> Result<Supplier, sqlx::Error> {
+        let supplier = sqlx::query_as!(
+            Supplier,
+            r#"
+            INSERT INTO suppliers (branch_id, name, contact, lead_time_days)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+            branch_id,
+            name,
+            contact,
+            lead_time_days
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(supplier)
+    }
+
+    pub async fn update(
+        db: &sqlx::PgPool,
+        id: Uuid,
+        name: String,
+        contact: Option<String>,
+        lead_time_days: Option<i32>,
+    ) -> Result<Supplier, sqlx::Error> {
+        let supplier = sqlx::query_as!(
+            Supplier,
+            r#"
+            UPDATE suppliers
+            SET name = $1, contact = $2, lead_time_days = $3, updated_at = now()
+            WHERE id = $4
+            RETURNING *
+            "#,
+            name,
+            contact,
+            lead_time_days,
+            id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(supplier)
+    }
+
+    pub async fn get_by_id(db: &sqlx::PgPool, id: Uuid) -> Result<Supplier, sqlx::Error> {
+        let supplier = sqlx::query_as!(
+            Supplier,
+            r#"
+            SELECT * FROM suppliers
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(supplier)
+    }
+
+    pub async fn get_by_branch_id(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+    ) -> Result<Vec<Supplier>, sqlx::Error> {
+        let suppliers = sqlx::query_as!(
+            Supplier,
+            r#"
+            SELECT * FROM suppliers
+            WHERE branch_id = $1 AND deleted_at IS NULL
+            ORDER BY name ASC
+            "#,
+            branch_id
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(suppliers)
+    }
+
+    pub async fn delete(db: &sqlx::PgPool, id: Uuid) -> Result<Supplier, sqlx::Error> {
+        let supplier = sqlx::query_as!(
+            Supplier,
+            r#"
+            UPDATE suppliers
+            SET deleted_at = now()
+            WHERE id = $1
+            RETURNING *
+            "#,
+            id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(supplier)
+    }
+}
+
+impl SpecificationSupplier {
+    pub async fn upsert(
+        db: &sqlx::PgPool,
+        specification_id: Uuid,
+        supplier_id: Uuid,
+        pack_size: i32,
+        price: Decimal,
+    ) -> Result<SpecificationSupplier, sqlx::Error> {
+        let specification_supplier = sqlx::query_as!(
+            SpecificationSupplier,
+            r#"
+            INSERT INTO specification_suppliers (specification_id, supplier_id, pack_size, price)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (specification_id, supplier_id) DO UPDATE
+            SET pack_size = EXCLUDED.pack_size,
+                price = EXCLUDED.price,
+                updated_at = now(),
+                deleted_at = NULL
+            RETURNING *
+            "#,
+            specification_id,
+            supplier_id,
+            pack_size,
+            price
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(specification_supplier)
+    }
+
+    // cheapest supplier per smallest unit first, with what we actually paid them so far
+    pub async fn get_comparison_by_specification_id(
+        db: &sqlx::PgPool,
+        specification_id: Uuid,
+    ) -> Result<Vec<SpecificationSupplierComparison>, sqlx::Error> {
+        let comparisons = sqlx::query_as!(
+            SpecificationSupplierComparison,
+            r#"
+            SELECT
+                su.id AS supplier_id,
+                su.name AS supplier_name,
+                su.lead_time_days,
+                ss.pack_size,
+                ss.price,
+                (ss.price / ss.pack_size) AS unit_price,
+                (
+                    SELECT sh.unit_price
+                    FROM specification_histories sh
+                    WHERE sh.specification_id = ss.specification_id
+                        AND sh.supplier_id = ss.supplier_id
+                        AND sh.flow_type = 'IN'
+                        AND sh.deleted_at IS NULL
+                    ORDER BY sh.created_at DESC
+                    LIMIT 1
+                ) AS last_purchase_unit_price,
+                (
+                    SELECT coalesce(sum(sh.quantity), 0)
+                    FROM specification_histories sh
+                    WHERE sh.specification_id = ss.specification_id
+                        AND sh.supplier_id = ss.supplier_id
+                        AND sh.flow_type = 'IN'
+                        AND sh.deleted_at IS NULL
+                ) AS purchased_quantity
+            FROM
+                specification_suppliers ss
+                INNER JOIN suppliers su ON su.id = ss.supplier_id
+            WHERE
+                ss.specification_id = $1
+                AND ss.deleted_at IS NULL
+                AND su.deleted_at IS NULL
+            ORDER BY
+                (ss.price / ss.pack_size) ASC
+            "#,
+            specification_id
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(comparisons)
+    }
+}
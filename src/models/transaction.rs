@@ -1,7 +1,10 @@
 use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::requests::transaction::RequestQueryTransaction;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Transaction {
     pub id: Uuid,
@@ -11,6 +14,11 @@ pub struct Transaction {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub deleted_at: Option<NaiveDateTime>,
+    pub voided_by: Option<Uuid>,
+    pub void_reason: Option<String>,
+    pub reference_id: Option<Uuid>,
+    pub order_status: String,
+    pub payment_status: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -20,29 +28,50 @@ pub struct TransactionItem {
     pub product_id: Uuid,
     pub product_name: String,
     pub product_reference_id: Uuid,
-    pub product_quantity: i32,
+    pub product_quantity: Decimal,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub deleted_at: Option<NaiveDateTime>,
+    pub refunded_quantity: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SimplifyTransaction {
     pub id: Uuid,
+    pub reference_id: Option<Uuid>,
     pub created_by: Option<Uuid>,
     pub note: Option<String>,
+    pub order_status: String,
+    pub payment_status: String,
     pub created_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
 
     pub items: Option<Vec<SimplifyTransactionItem>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, sqlx::Type)]
 pub struct SimplifyTransactionItem {
+    pub id: Option<Uuid>,
+    pub product_id: Option<Uuid>,
+    pub product_name: Option<String>,
+    pub product_reference_id: Option<Uuid>,
+    pub product_quantity: Option<Decimal>,
+    pub refunded_quantity: Option<Decimal>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransactionMovement {
     pub id: Uuid,
-    pub product_id: Uuid,
-    pub product_name: String,
-    pub product_reference_id: Uuid,
-    pub product_quantity: i32,
+    pub transaction_item_id: Uuid,
+    pub specification_id: Uuid,
+    pub specification_name: String,
+    pub unit_name: String,
+    pub flow_type: String,
+    pub quantity: Decimal,
+    pub price: Option<Decimal>,
+    pub unit_price: Option<Decimal>,
+    pub note: Option<String>,
     pub created_at: NaiveDateTime,
 }
 
@@ -50,19 +79,117 @@ impl Transaction {
     pub async fn create(
         db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         branch_id: &Uuid,
+        reference_id: Option<Uuid>,
         created_by: Option<Uuid>,
         note: Option<String>,
-    ) -> Result<Transaction, sqlx::Error> {
+        order_status: String,
+        payment_status: String,
+    ) -> Result<Option<Transaction>, sqlx::Error> {
+        // None when the branch already has a transaction with this reference_id
         let transaction = sqlx::query_as!(
             Transaction,
             r#"
-            INSERT INTO transactions (branch_id, created_by, note)
-            VALUES ($1, $2, $3)
+            INSERT INTO transactions (branch_id, reference_id, created_by, note, order_status, payment_status)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (branch_id, reference_id) DO NOTHING
             RETURNING *
             "#,
             branch_id,
+            reference_id,
             created_by,
-            note
+            note,
+            order_status,
+            payment_status
+        )
+        .fetch_optional(db_trx)
+        .await?;
+
+        Ok(transaction)
+    }
+
+    pub async fn get_by_reference_id(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        branch_id: Uuid,
+        reference_id: Uuid,
+    ) -> Result<Transaction, sqlx::Error> {
+        let transaction = sqlx::query_as!(
+            Transaction,
+            r#"
+            SELECT * FROM transactions
+            WHERE branch_id = $1 AND reference_id = $2
+            "#,
+            branch_id,
+            reference_id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(transaction)
+    }
+
+    // lock the transaction so a void and a refund of the same order cannot reverse stock twice
+    pub async fn get_by_id_for_update(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        branch_id: Uuid,
+        id: Uuid,
+    ) -> Result<Transaction, sqlx::Error> {
+        let transaction = sqlx::query_as!(
+            Transaction,
+            r#"
+            SELECT * FROM transactions
+            WHERE id = $1 AND branch_id = $2 AND deleted_at IS NULL
+            FOR UPDATE
+            "#,
+            id,
+            branch_id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(transaction)
+    }
+
+    pub async fn update_status(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+        order_status: String,
+        payment_status: String,
+    ) -> Result<Transaction, sqlx::Error> {
+        let transaction = sqlx::query_as!(
+            Transaction,
+            r#"
+            UPDATE transactions
+            SET order_status = $1, payment_status = $2, updated_at = now()
+            WHERE id = $3
+            RETURNING *
+            "#,
+            order_status,
+            payment_status,
+            id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(transaction)
+    }
+
+    pub async fn void(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+        voided_by: Uuid,
+        void_reason: Option<String>,
+    ) -> Result<Transaction, sqlx::Error> {
+        let transaction = sqlx::query_as!(
+            Transaction,
+            r#"
+            UPDATE transactions
+            SET deleted_at = now(), voided_by = $1, void_reason = $2, updated_at = now()
+            WHERE id = $3 AND deleted_at IS NULL
+            RETURNING *
+            "#,
+            voided_by,
+            void_reason,
+            id
         )
         .fetch_one(db_trx)
         .await?;
@@ -78,7 +205,7 @@ impl TransactionItem {
         product_id: Uuid,
         product_name: String,
         product_reference_id: Uuid,
-        product_quantity: i32,
+        product_quantity: Decimal,
     ) -> Result<TransactionItem, sqlx::Error> {
         let transaction_item = sqlx::query_as!(
             TransactionItem,
@@ -98,55 +225,210 @@ impl TransactionItem {
 
         Ok(transaction_item)
     }
+
+    pub async fn get_by_transaction_id(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        transaction_id: Uuid,
+    ) -> Result<Vec<TransactionItem>, sqlx::Error> {
+        let transaction_items = sqlx::query_as!(
+            TransactionItem,
+            r#"
+            SELECT * FROM transaction_items
+            WHERE transaction_id = $1 AND deleted_at IS NULL
+            ORDER BY created_at ASC
+            "#,
+            transaction_id
+        )
+        .fetch_all(db_trx)
+        .await?;
+
+        Ok(transaction_items)
+    }
+
+    // fails when the item would end up refunded beyond what was sold
+    pub async fn refund(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+        quantity: Decimal,
+    ) -> Result<TransactionItem, sqlx::Error> {
+        let transaction_item = sqlx::query_as!(
+            TransactionItem,
+            r#"
+            UPDATE transaction_items
+            SET refunded_quantity = refunded_quantity + $1, updated_at = now()
+            WHERE id = $2 AND refunded_quantity + $1 <= product_quantity
+            RETURNING *
+            "#,
+            quantity,
+            id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(transaction_item)
+    }
 }
 
 impl SimplifyTransaction {
-    // pub async fn get_by_id_with_items(db: &sqlx::PgPool, id: Uuid) -> Result<Self, sqlx::Error> {
-    //     let transaction = sqlx::query_as!(
-    //         SimplifyTransaction,
-    //         r#"
-    //             SELECT
-    //                 t.id,
-    //                 t.created_by,
-    //                 t.note,
-    //                 coalesce(array_agg((ti.id, ti.product_id, ti.product_name, ti.product_reference_id, ti.product_quantity, ti.created_at)) FILTER (WHERE ti.id IS NOT NULL AND ti.deleted_at IS NULL), '{}') AS "items: Vec<SimplifyTransactionItem>",
-    //                 t.created_at
-    //             FROM
-    //                 transactions t
-    //                 LEFT JOIN transaction_items ti ON ti.transaction_id = t.id
-    //             WHERE
-    //                 t.id = $1
-    //             GROUP BY
-    //                 t.id
-    //         "#,
-    //         id
-    //     )
-    //     .fetch_one(db)
-    //     .await?;
-
-    //     Ok(transaction)
-    // }
-
-//    pub async fn get_all_with_items(db: &sqlx::PgPool) -> Result<Vec<Self>, sqlx::Error> {
-//        let transactions = sqlx::query_as!(
-//            SimplifyTransaction,
-//            r#"
-//                SELECT
-//                    t.id,
-//                    t.created_by,
-//                    t.note,
-//                    coalesce(array_agg((ti.id, ti.product_id, ti.product_name, ti.product_reference_id, ti.product_quantity, ti.created_at)) FILTER (WHERE ti.id IS NOT NULL AND ti.deleted_at IS NULL), '{}') AS "items: Vec<SimplifyTransactionItem>",
-//                    t.created_at
-//                FROM
-//                    transactions t
-//                    LEFT JOIN transaction_items ti ON ti.transaction_id = t.id
-//                GROUP BY
-//                    t.id
-//            "#
-//        )
-//        .fetch_all(db)
-//        .await?;
-//
-//        Ok(transactions)
-//    }
+    pub async fn get_by_branch_id(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+        query: &RequestQueryTransaction,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let transactions = sqlx::query_as!(
+            SimplifyTransaction,
+            r#"
+            SELECT
+                t.id,
+                t.reference_id,
+                t.created_by,
+                t.note,
+                t.order_status,
+                t.payment_status,
+                t.created_at,
+                t.deleted_at,
+                coalesce(array_agg((ti.id, ti.product_id, ti.product_name, ti.product_reference_id, ti.product_quantity, ti.refunded_quantity, ti.created_at)
+                ORDER BY
+                    ti.created_at ASC) FILTER (WHERE ti.id IS NOT NULL AND ti.deleted_at IS NULL), '{}') AS "items: Vec<SimplifyTransactionItem>"
+            FROM
+                transactions t
+                LEFT JOIN transaction_items ti ON ti.transaction_id = t.id
+            WHERE
+                t.branch_id = $1
+                AND t.deleted_at IS NULL
+                AND ($2::TIMESTAMP IS NULL OR t.created_at >= $2)
+                AND ($3::TIMESTAMP IS NULL OR t.created_at <= $3)
+                AND ($4::uuid IS NULL OR EXISTS (SELECT 1 FROM transaction_items pti WHERE pti.transaction_id = t.id AND pti.product_id = $4))
+                AND ($5::uuid IS NULL OR t.created_by = $5)
+            GROUP BY
+                t.id
+            ORDER BY
+                t.created_at DESC
+            LIMIT $6 OFFSET $7
+            "#,
+            branch_id,
+            query.from.map(|from| from.naive_utc()),
+            query.to.map(|to| to.naive_utc()),
+            query.product_id,
+            query.created_by,
+            limit,
+            offset
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(transactions)
+    }
+
+    pub async fn count_by_branch_id(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+        query: &RequestQueryTransaction,
+    ) -> Result<i64, sqlx::Error> {
+        let total = sqlx::query!(
+            r#"
+            SELECT count(*) AS "total!"
+            FROM transactions t
+            WHERE
+                t.branch_id = $1
+                AND t.deleted_at IS NULL
+                AND ($2::TIMESTAMP IS NULL OR t.created_at >= $2)
+                AND ($3::TIMESTAMP IS NULL OR t.created_at <= $3)
+                AND ($4::uuid IS NULL OR EXISTS (SELECT 1 FROM transaction_items pti WHERE pti.transaction_id = t.id AND pti.product_id = $4))
+                AND ($5::uuid IS NULL OR t.created_by = $5)
+            "#,
+            branch_id,
+            query.from.map(|from| from.naive_utc()),
+            query.to.map(|to| to.naive_utc()),
+            query.product_id,
+            query.created_by
+        )
+        .fetch_one(db)
+        .await?
+        .total;
+
+        Ok(total)
+    }
+
+    // voided transactions stay readable here so their reversals can be audited
+    pub async fn get_by_id_with_items(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let transaction = sqlx::query_as!(
+            SimplifyTransaction,
+            r#"
+            SELECT
+                t.id,
+                t.reference_id,
+                t.created_by,
+                t.note,
+                t.order_status,
+                t.payment_status,
+                t.created_at,
+                t.deleted_at,
+                coalesce(array_agg((ti.id, ti.product_id, ti.product_name, ti.product_reference_id, ti.product_quantity, ti.refunded_quantity, ti.created_at)
+                ORDER BY
+                    ti.created_at ASC) FILTER (WHERE ti.id IS NOT NULL AND ti.deleted_at IS NULL), '{}') AS "items: Vec<SimplifyTransactionItem>"
+            FROM
+                transactions t
+                LEFT JOIN transaction_items ti ON ti.transaction_id = t.id
+            WHERE
+                t.id = $1
+                AND t.branch_id = $2
+            GROUP BY
+                t.id
+            "#,
+            id,
+            branch_id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(transaction)
+    }
+}
+
+impl TransactionMovement {
+    // every specification history a transaction item caused, its OUT rows and any refund or void IN rows
+    pub async fn get_by_transaction_id(
+        db: &sqlx::PgPool,
+        transaction_id: Uuid,
+    ) -> Result<Vec<TransactionMovement>, sqlx::Error> {
+        let movements = sqlx::query_as!(
+            TransactionMovement,
+            r#"
+            SELECT
+                sh.id,
+                ti.id AS transaction_item_id,
+                s.id AS specification_id,
+                s.name AS specification_name,
+                s.unit_name,
+                sh.flow_type,
+                sh.quantity,
+                sh.price,
+                sh.unit_price,
+                sh.note,
+                sh.created_at
+            FROM
+                specification_histories sh
+                INNER JOIN transaction_items ti ON ti.id = sh.transaction_item_id
+                INNER JOIN specifications s ON s.id = sh.specification_id
+            WHERE
+                ti.transaction_id = $1
+                AND sh.deleted_at IS NULL
+            ORDER BY
+                sh.created_at ASC,
+                s.name ASC
+            "#,
+            transaction_id
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(movements)
+    }
 }
@@ -0,0 +1,143 @@
+use chrono::NaiveDateTime;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::FieldErrorCode;
+
+use super::specification::Specification;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Unit {
+    pub id: Uuid,
+    pub branch_id: Option<Uuid>,
+    pub code: String,
+    pub name: String,
+    pub dimension: String,
+    pub factor: f64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+impl Unit {
+    pub async fn create(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+        code: String,
+        name: String,
+        dimension: String,
+        factor: f64,
+    ) -> Result<Unit, sqlx::Error> {
+        let unit = sqlx::query_as!(
+            Unit,
+            r#"
+            INSERT INTO units (branch_id, code, name, dimension, factor)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+            branch_id,
+            code,
+            name,
+            dimension,
+            factor
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(unit)
+    }
+
+    pub async fn get_by_id(db: &sqlx::PgPool, id: Uuid) -> Result<Unit, sqlx::Error> {
+        let unit = sqlx::query_as!(
+            Unit,
+            r#"
+            SELECT * FROM units
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(unit)
+    }
+
+    // global units plus the ones defined by the branch
+    pub async fn get_by_branch_id(
+        db: &sqlx::PgPool,
+        branch_id: Uuid,
+    ) -> Result<Vec<Unit>, sqlx::Error> {
+        let units = sqlx::query_as!(
+            Unit,
+            r#"
+            SELECT * FROM units
+            WHERE (branch_id IS NULL OR branch_id = $1) AND deleted_at IS NULL
+            ORDER BY dimension ASC, factor ASC
+            "#,
+            branch_id
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(units)
+    }
+
+    // a unit defined by the branch wins over a global unit with the same code
+    pub async fn get_by_code_or_name(
+        db: &sqlx::PgPool,
+        branch_id: &Uuid,
+        code: &str,
+    ) -> Result<Unit, sqlx::Error> {
+        let unit = sqlx::query_as!(
+            Unit,
+            r#"
+            SELECT * FROM units
+            WHERE (branch_id IS NULL OR branch_id = $1)
+                AND (code = lower($2) OR name = lower($2))
+                AND deleted_at IS NULL
+            ORDER BY branch_id IS NULL ASC
+            LIMIT 1
+            "#,
+            branch_id,
+            code
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(unit)
+    }
+
+    pub fn convert(&self, quantity: Decimal, to: &Unit) -> Result<Decimal, FieldErrorCode> {
+        if self.dimension != to.dimension {
+            return Err("unit is not compatible with the specification unit");
+        }
+
+        let from_factor = Decimal::from_f64(self.factor).ok_or("unit factor is invalid")?;
+        let to_factor = Decimal::from_f64(to.factor).ok_or("unit factor is invalid")?;
+
+        Ok((quantity * from_factor / to_factor).normalize())
+    }
+
+    // convert a quantity given in `code` into the unit the specification is stocked in
+    pub async fn convert_to_specification_unit(
+        db: &sqlx::PgPool,
+        specification: &Specification,
+        code: &str,
+        quantity: Decimal,
+    ) -> Result<Decimal, FieldErrorCode> {
+        let specification_unit = match specification.unit_id {
+            Some(unit_id) => Unit::get_by_id(db, unit_id)
+                .await
+                .map_err(|_| "specification unit not found")?,
+            None => return Err("specification has no unit in the catalog"),
+        };
+
+        let unit = Unit::get_by_code_or_name(db, &specification.branch_id, code)
+            .await
+            .map_err(|_| "unit not found")?;
+
+        unit.convert(quantity, &specification_unit)
+    }
+}
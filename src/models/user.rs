@@ -7,33 +7,20 @@ pub struct User {
     pub id: Uuid,
     pub name: String,
     pub email: String,
+    #[serde(skip_serializing)]
     pub password: String,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub deleted_at: Option<NaiveDateTime>,
+    pub email_verified_at: Option<NaiveDateTime>,
+    #[serde(skip_serializing)]
+    pub totp_secret: Option<String>,
+    pub totp_enabled_at: Option<NaiveDateTime>,
+    #[serde(skip_serializing)]
+    pub totp_last_step: Option<i64>,
 }
 
 impl User {
-    pub async fn login(
-        db: &sqlx::PgPool,
-        email: String,
-        password: String,
-    ) -> Result<User, sqlx::Error> {
-        let user = sqlx::query_as!(
-            User,
-            r#"
-            SELECT * FROM users
-            WHERE email = $1 AND password = $2
-            "#,
-            email,
-            password
-        )
-        .fetch_one(db)
-        .await?;
-
-        Ok(user)
-    }
-
     pub async fn create(
         db: &sqlx::PgPool,
         name: String,
@@ -97,4 +84,135 @@ impl User {
 
         Ok(user)
     }
+
+    pub async fn update_password(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+        password: String,
+    ) -> Result<User, sqlx::Error> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users
+            SET password = $1, updated_at = now()
+            WHERE id = $2
+            RETURNING *
+            "#,
+            password,
+            id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(user)
+    }
+
+    pub async fn verify_email(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+    ) -> Result<User, sqlx::Error> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users
+            SET email_verified_at = coalesce(email_verified_at, now()), updated_at = now()
+            WHERE id = $1
+            RETURNING *
+            "#,
+            id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(user)
+    }
+
+    // a fresh secret waiting to be confirmed with a first code
+    pub async fn update_totp_secret(
+        db: &sqlx::PgPool,
+        id: Uuid,
+        totp_secret: String,
+    ) -> Result<User, sqlx::Error> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users
+            SET totp_secret = $1, totp_enabled_at = NULL, totp_last_step = NULL, updated_at = now()
+            WHERE id = $2
+            RETURNING *
+            "#,
+            totp_secret,
+            id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(user)
+    }
+
+    pub async fn enable_totp(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+        totp_last_step: i64,
+    ) -> Result<User, sqlx::Error> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users
+            SET totp_enabled_at = now(), totp_last_step = $1, updated_at = now()
+            WHERE id = $2
+            RETURNING *
+            "#,
+            totp_last_step,
+            id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(user)
+    }
+
+    pub async fn disable_totp(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+    ) -> Result<User, sqlx::Error> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users
+            SET totp_secret = NULL, totp_enabled_at = NULL, totp_last_step = NULL, updated_at = now()
+            WHERE id = $1
+            RETURNING *
+            "#,
+            id
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(user)
+    }
+
+    // fails when a code of this or a later step was already accepted, two requests racing with the
+    // same code can't both pass
+    pub async fn use_totp_step(
+        db: &sqlx::PgPool,
+        id: Uuid,
+        totp_last_step: i64,
+    ) -> Result<User, sqlx::Error> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users
+            SET totp_last_step = $1, updated_at = now()
+            WHERE id = $2 AND (totp_last_step IS NULL OR totp_last_step < $1)
+            RETURNING *
+            "#,
+            totp_last_step,
+            id
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(user)
+    }
 }
\ No newline at end of file
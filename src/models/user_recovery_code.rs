@@ -0,0 +1,76 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UserRecoveryCode {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub code: String,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl UserRecoveryCode {
+    // drops every code of the user, used or not, before storing the new set
+    pub async fn replace_by_user_id(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_id: Uuid,
+        codes: Vec<String>,
+    ) -> Result<u64, sqlx::Error> {
+        UserRecoveryCode::delete_by_user_id(db_trx, user_id).await?;
+
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO user_recovery_codes (user_id, code)
+            SELECT $1, unnest($2::VARCHAR[])
+            "#,
+            user_id,
+            &codes
+        )
+        .execute(db_trx)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn consume(
+        db: &sqlx::PgPool,
+        user_id: Uuid,
+        code: String,
+    ) -> Result<UserRecoveryCode, sqlx::Error> {
+        let user_recovery_code = sqlx::query_as!(
+            UserRecoveryCode,
+            r#"
+            UPDATE user_recovery_codes
+            SET used_at = now(), updated_at = now()
+            WHERE user_id = $1 AND code = $2 AND used_at IS NULL
+            RETURNING *
+            "#,
+            user_id,
+            code
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(user_recovery_code)
+    }
+
+    pub async fn delete_by_user_id(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM user_recovery_codes
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(db_trx)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
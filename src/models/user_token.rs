@@ -0,0 +1,77 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UserToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub r#type: String,
+    pub token: String,
+    pub expires_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl UserToken {
+    // only the newest token of a type stays usable, older unused ones are dropped
+    pub async fn create(
+        db: &sqlx::PgPool,
+        user_id: Uuid,
+        r#type: &str,
+        token: String,
+        expires_at: NaiveDateTime,
+    ) -> Result<UserToken, sqlx::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM user_tokens
+            WHERE user_id = $1 AND type = $2 AND used_at IS NULL
+            "#,
+            user_id,
+            r#type
+        )
+        .execute(db)
+        .await?;
+
+        let user_token = sqlx::query_as!(
+            UserToken,
+            r#"
+            INSERT INTO user_tokens (user_id, type, token, expires_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+            user_id,
+            r#type,
+            token,
+            expires_at
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(user_token)
+    }
+
+    // marks the token used in the same statement that finds it, so it can only be redeemed once
+    pub async fn consume(
+        db_trx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        r#type: &str,
+        token: String,
+    ) -> Result<UserToken, sqlx::Error> {
+        let user_token = sqlx::query_as!(
+            UserToken,
+            r#"
+            UPDATE user_tokens
+            SET used_at = now(), updated_at = now()
+            WHERE token = $1 AND type = $2 AND used_at IS NULL AND expires_at > now()
+            RETURNING *
+            "#,
+            token,
+            r#type
+        )
+        .fetch_one(db_trx)
+        .await?;
+
+        Ok(user_token)
+    }
+}
@@ -0,0 +1,87 @@
+use argon2::{self, Config, ThreadMode, Variant, Version};
+
+// argon2id with a random 16 byte salt per hash, the salt and parameters travel in the encoded string
+fn config<'a>() -> Config<'a> {
+    Config {
+        variant: Variant::Argon2id,
+        version: Version::Version13,
+        mem_cost: 19456,
+        time_cost: 2,
+        lanes: 1,
+        thread_mode: ThreadMode::Sequential,
+        secret: &[],
+        ad: &[],
+        hash_length: 32,
+    }
+}
+
+// argon2 is slow on purpose, run it off the async workers so it doesn't stall other requests
+pub async fn hash(password: &str) -> String {
+    let password = password.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let mut salt = [0u8; 16];
+        getrandom::getrandom(&mut salt).unwrap();
+
+        argon2::hash_encoded(password.as_bytes(), &salt, &config()).unwrap()
+    })
+    .await
+    .unwrap()
+}
+
+pub async fn verify(hash: &str, password: &str) -> bool {
+    let hash = hash.to_string();
+    let password = password.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        if is_legacy(&hash) {
+            // hashes written before per-user salts were made from the trimmed, lowercased password
+            let legacy_password = password.trim().to_lowercase();
+
+            return argon2::verify_encoded(&hash, legacy_password.as_bytes()).unwrap_or(false);
+        }
+
+        argon2::verify_encoded(&hash, password.as_bytes()).unwrap_or(false)
+    })
+    .await
+    .unwrap()
+}
+
+// legacy hashes are argon2i salted with APPKEY, they get replaced on the next successful login
+pub fn is_legacy(hash: &str) -> bool {
+    !hash.starts_with("$argon2id$")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // how hashes were made before per-user salts, argon2i with APPKEY as the salt
+    fn legacy_hash(password: &str) -> String {
+        argon2::hash_encoded(password.as_bytes(), b"base64:app-key-salt", &Config::default()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn verify_legacy_hash_with_trimmed_lowercased_password() {
+        let hash = legacy_hash("secret123");
+
+        assert!(is_legacy(&hash));
+        assert!(verify(&hash, "secret123").await);
+        assert!(verify(&hash, "  Secret123 ").await);
+        assert!(!verify(&hash, "secret124").await);
+    }
+
+    #[tokio::test]
+    async fn verify_new_hash_is_exact() {
+        let hash = hash("Secret123").await;
+
+        assert!(!is_legacy(&hash));
+        assert!(verify(&hash, "Secret123").await);
+        assert!(!verify(&hash, "secret123").await);
+    }
+
+    #[tokio::test]
+    async fn hash_uses_a_fresh_salt() {
+        assert_ne!(hash("Secret123").await, hash("Secret123").await);
+    }
+}
@@ -0,0 +1,12 @@
+use crypto_hash::{hex_digest, Algorithm};
+use uuid::Uuid;
+
+// 64 hex characters from two v4 uuids, both drawn from the OS random generator
+pub fn generate() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+// tokens that must survive a database leak are only stored as their sha256
+pub fn digest(token: &str) -> String {
+    hex_digest(Algorithm::SHA256, token.as_bytes())
+}
@@ -0,0 +1,84 @@
+use totp_rs::{Algorithm, Secret, TOTP};
+use uuid::Uuid;
+
+const ISSUER: &str = "Maresto Stock Opname";
+const STEP: u64 = 30;
+
+// sha1, 6 digits and 30 second steps, what every authenticator app expects by default
+fn totp(secret: &str, account_name: &str) -> Option<TOTP> {
+    let secret = Secret::Encoded(secret.to_string()).to_bytes().ok()?;
+
+    TOTP::new(Algorithm::SHA1, 6, 0, STEP, secret, Some(ISSUER.to_string()), account_name.to_string()).ok()
+}
+
+// base32, the form the secret is stored in and shown to the user
+pub fn generate_secret() -> String {
+    match Secret::generate_secret().to_encoded() {
+        Secret::Encoded(secret) => secret,
+        Secret::Raw(_) => unreachable!(),
+    }
+}
+
+pub fn otpauth_uri(secret: &str, email: &str) -> Option<String> {
+    totp(secret, &email.replace(':', "")).map(|totp| totp.get_url())
+}
+
+// accepts the previous, current and next step for clock drift, but never a step at or before
+// last_step, returns the step the code belongs to
+pub fn verify(secret: &str, code: &str, last_step: Option<i64>) -> Option<i64> {
+    let totp = totp(secret, "")?;
+    let current_step = (chrono::Utc::now().timestamp() as u64 / STEP) as i64;
+
+    (current_step - 1..=current_step + 1)
+        .filter(|step| last_step.is_none_or(|last_step| *step > last_step))
+        .find(|step| totp.check(code, *step as u64 * STEP))
+}
+
+// ten one-time codes like "1f0c3-9ab27", handed out when two-factor is enabled
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..10)
+        .map(|_| {
+            let code = Uuid::new_v4().simple().to_string();
+            format!("{}-{}", &code[..5], &code[5..10])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn current_code(secret: &str) -> (String, i64) {
+        let step = chrono::Utc::now().timestamp() / STEP as i64;
+
+        (totp(secret, "").unwrap().generate(step as u64 * STEP), step)
+    }
+
+    #[test]
+    fn verify_accepts_current_code() {
+        let secret = generate_secret();
+        let (code, step) = current_code(&secret);
+
+        assert_eq!(verify(&secret, &code, None), Some(step));
+        assert_eq!(verify(&secret, &code, Some(step - 1)), Some(step));
+    }
+
+    #[test]
+    fn verify_rejects_replayed_step() {
+        let secret = generate_secret();
+        let (code, step) = current_code(&secret);
+
+        assert_eq!(verify(&secret, &code, Some(step)), None);
+        assert_eq!(verify(&secret, &code, Some(step + 1)), None);
+    }
+
+    #[test]
+    fn verify_rejects_wrong_code_and_secret() {
+        let secret = generate_secret();
+        let (code, _) = current_code(&secret);
+        let wrong_code = format!("{:06}", (code.parse::<u32>().unwrap() + 1) % 1_000_000);
+
+        assert_eq!(verify(&secret, &wrong_code, None), None);
+        assert_eq!(verify("not base32!", &code, None), None);
+    }
+}